@@ -71,7 +71,7 @@ fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
 
             if let Some(compile_group) = &source
                 .compile_group_index
-                .and_then(|i| target.compile_groups.get(i))
+                .and_then(|i| target.compile_group(i))
             {
                 println!("    Includes:");
                 for include in &compile_group.includes {
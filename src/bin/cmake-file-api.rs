@@ -0,0 +1,449 @@
+//! Command line tool for inspecting cmake-file-api replies of a build directory
+//!
+//! Requires the `cli` feature: `cargo install cmake-file-api --features cli`
+
+use cmake_file_api::objects::codemodel_v2::{Configuration, Target};
+use cmake_file_api::{objects, reply};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cmake-file-api [OPTIONS] <COMMAND>
+
+Commands:
+  targets [--type <TYPE>] [--project <NAME>]  List targets
+  sources <TARGET>                            List sources of a target
+  flags <FILE>                                Show compile settings of a source file
+  deps <TARGET>                               List transitive dependencies of a target
+  cache list                                  List cache entries
+  cache get <NAME>                            Print the value of a cache entry
+  toolchains                                  List toolchains
+  inputs                                      List CMake input files
+  index                                       Show CMake version and generator
+
+Options:
+  -B, --build-dir <DIR>  Build directory [default: .]
+  --config <NAME>        Configuration of multi-config generators [default: first]
+  --json                 Print JSON instead of text
+  -h, --help             Print help
+";
+
+#[derive(Debug)]
+struct Options {
+    build_dir: PathBuf,
+    config: Option<String>,
+    json: bool,
+    type_filter: Option<String>,
+    project_filter: Option<String>,
+    command: Vec<String>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options {
+        build_dir: PathBuf::from("."),
+        config: None,
+        json: false,
+        type_filter: None,
+        project_filter: None,
+        command: Vec::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {name}"));
+        match arg.as_str() {
+            "-B" | "--build-dir" => options.build_dir = PathBuf::from(value(&arg)?),
+            "--config" => options.config = Some(value(&arg)?),
+            "--type" => options.type_filter = Some(value(&arg)?),
+            "--project" => options.project_filter = Some(value(&arg)?),
+            "--json" => options.json = true,
+            "-h" | "--help" => options.command = vec!["help".to_owned()],
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {arg}")),
+            _ => options.command.push(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let command: Vec<&str> = options.command.iter().map(String::as_str).collect();
+    if matches!(command.as_slice(), [] | ["help"]) {
+        print!("{USAGE}");
+        return Ok(());
+    }
+
+    let reader = reply::Reader::from_build_dir(&options.build_dir)?;
+
+    match command.as_slice() {
+        ["targets"] => targets(&reader, options),
+        ["sources", target] => sources(&reader, options, target),
+        ["flags", file] => flags(&reader, options, Path::new(file)),
+        ["deps", target] => deps(&reader, options, target),
+        ["cache", "list"] => cache_list(&reader, options),
+        ["cache", "get", name] => cache_get(&reader, options, name),
+        ["toolchains"] => toolchains(&reader, options),
+        ["inputs"] => inputs(&reader, options),
+        ["index"] => index(&reader, options),
+        _ => Err(format!("invalid command: {}\n\n{USAGE}", command.join(" ")).into()),
+    }
+}
+
+/// Print `value` as JSON or call `text` to print it as plain text
+fn output<T: Serialize>(
+    options: &Options,
+    value: &T,
+    text: impl FnOnce(&T),
+) -> Result<(), Box<dyn std::error::Error>> {
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        text(value);
+    }
+    Ok(())
+}
+
+fn configuration<'a>(
+    codemodel: &'a objects::CodeModelV2,
+    options: &Options,
+) -> Result<&'a Configuration, String> {
+    match &options.config {
+        Some(name) => codemodel
+            .configurations
+            .iter()
+            .find(|config| &config.name == name)
+            .ok_or(format!("configuration not found: {name}")),
+        None => codemodel
+            .configurations
+            .first()
+            .ok_or_else(|| "codemodel has no configurations".to_owned()),
+    }
+}
+
+fn find_target<'a>(config: &'a Configuration, name: &str) -> Result<&'a Target, String> {
+    config
+        .targets
+        .iter()
+        .find(|target| target.name == name)
+        .ok_or(format!("target not found: {name}"))
+}
+
+fn targets(reader: &reply::Reader, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let codemodel: objects::CodeModelV2 = reader.read_object()?;
+    let config = configuration(&codemodel, options)?;
+
+    let rows: Vec<_> = config
+        .target_refs
+        .iter()
+        .zip(&config.targets)
+        .filter(|(_, target)| {
+            options.type_filter.as_ref().map_or(true, |type_name| {
+                target.type_name.eq_ignore_ascii_case(type_name)
+            })
+        })
+        .filter(|(target_ref, _)| {
+            options.project_filter.as_ref().map_or(true, |project| {
+                config
+                    .project(target_ref.project_index)
+                    .map_or(false, |p| &p.name == project)
+            })
+        })
+        .map(|(target_ref, target)| {
+            json!({
+                "name": target.name,
+                "id": target.id,
+                "type": target.type_name,
+                "project": config.project(target_ref.project_index).map(|p| &p.name),
+                "artifacts": target.artifacts.iter().map(|a| &a.path).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    output(options, &rows, |rows| {
+        for row in rows {
+            println!(
+                "{}\t{}",
+                row["name"].as_str().unwrap_or_default(),
+                row["type"].as_str().unwrap_or_default()
+            );
+        }
+    })
+}
+
+fn sources(
+    reader: &reply::Reader,
+    options: &Options,
+    target_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let codemodel: objects::CodeModelV2 = reader.read_object()?;
+    let target = find_target(configuration(&codemodel, options)?, target_name)?;
+
+    output(options, &target.sources, |sources| {
+        for source in sources {
+            let language = source
+                .compile_group_index
+                .and_then(|index| target.compile_group(index))
+                .map_or("", |group| group.language.as_str());
+            println!("{}\t{language}", source.path.display());
+        }
+    })
+}
+
+fn flags(
+    reader: &reply::Reader,
+    options: &Options,
+    file: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let codemodel: objects::CodeModelV2 = reader.read_object()?;
+    let config = configuration(&codemodel, options)?;
+
+    let rows = compile_rows(&codemodel, config, file, &std::env::current_dir()?);
+    if rows.is_empty() {
+        return Err(format!("no target compiles {}", file.display()).into());
+    }
+
+    output(options, &rows, |rows| {
+        for row in rows {
+            println!(
+                "{} ({})",
+                row["target"].as_str().unwrap_or_default(),
+                row["language"].as_str().unwrap_or_default()
+            );
+            for (key, prefix) in [("defines", "-D"), ("includes", "-I"), ("flags", "")] {
+                for value in row[key].as_array().into_iter().flatten() {
+                    println!("  {prefix}{}", value.as_str().unwrap_or_default());
+                }
+            }
+        }
+    })
+}
+
+/// Compile settings of all targets compiling `file`
+///
+/// Relative paths are tried relative to `cwd` first and then relative to the top-level source directory.
+fn compile_rows(
+    codemodel: &objects::CodeModelV2,
+    config: &Configuration,
+    file: &Path,
+    cwd: &Path,
+) -> Vec<serde_json::Value> {
+    let index = codemodel.source_index();
+    let mut usages = index.lookup_in(cwd.join(file), &config.name);
+    if usages.is_empty() {
+        usages = index.lookup_in(file, &config.name);
+    }
+
+    let mut rows = Vec::new();
+    for usage in usages {
+        let (source, group) = match (usage.source(codemodel), usage.compile_group(codemodel)) {
+            (Some(source), Some(group)) => (source, group),
+            _ => continue,
+        };
+        rows.push(json!({
+            "target": usage.target,
+            "source": source.path,
+            "language": group.language,
+            "standard": group.language_standard.as_ref().map(|s| &s.standard),
+            "defines": group.defines(),
+            "includes": group.includes.iter().map(|i| &i.path).collect::<Vec<_>>(),
+            "flags": group.flags(),
+        }));
+    }
+    rows
+}
+
+fn deps(
+    reader: &reply::Reader,
+    options: &Options,
+    target_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let codemodel: objects::CodeModelV2 = reader.read_object()?;
+    let config = configuration(&codemodel, options)?;
+    let root = find_target(config, target_name)?;
+
+    // breadth-first walk over the dependency graph
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut queue = VecDeque::from([(root, 0usize)]);
+    let mut rows = Vec::new();
+    while let Some((target, depth)) = queue.pop_front() {
+        for dependency in &target.dependencies {
+            if !seen.insert(dependency.id.as_str()) {
+                continue;
+            }
+            if let Some(dependency_target) = config.targets.iter().find(|t| t.id == dependency.id) {
+                rows.push(json!({
+                    "name": dependency_target.name,
+                    "id": dependency_target.id,
+                    "type": dependency_target.type_name,
+                    "depth": depth + 1,
+                }));
+                queue.push_back((dependency_target, depth + 1));
+            }
+        }
+    }
+
+    output(options, &rows, |rows| {
+        for row in rows {
+            let depth = row["depth"].as_u64().unwrap_or(1);
+            println!(
+                "{}{}\t{}",
+                "  ".repeat(usize::try_from(depth - 1).unwrap_or_default()),
+                row["name"].as_str().unwrap_or_default(),
+                row["type"].as_str().unwrap_or_default()
+            );
+        }
+    })
+}
+
+fn cache_list(reader: &reply::Reader, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let cache: objects::CacheV2 = reader.read_object()?;
+    output(options, &cache.entries, |entries| {
+        for entry in entries {
+            println!("{}:{}={}", entry.name, entry.type_name, entry.value);
+        }
+    })
+}
+
+fn cache_get(
+    reader: &reply::Reader,
+    options: &Options,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cache: objects::CacheV2 = reader.read_object()?;
+    let entry = cache
+        .entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .ok_or(format!("cache entry not found: {name}"))?;
+    output(options, entry, |entry| println!("{}", entry.value))
+}
+
+fn toolchains(reader: &reply::Reader, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let toolchains: objects::ToolchainsV1 = reader.read_object()?;
+    output(options, &toolchains.toolchains, |toolchains| {
+        for toolchain in toolchains {
+            let compiler = &toolchain.compiler;
+            println!(
+                "{}\t{} {}\t{}",
+                toolchain.language,
+                compiler.id.as_deref().unwrap_or("?"),
+                compiler.version.as_deref().unwrap_or("?"),
+                compiler
+                    .path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default()
+            );
+        }
+    })
+}
+
+fn inputs(reader: &reply::Reader, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let cmake_files: objects::CMakeFilesV1 = reader.read_object()?;
+    output(options, &cmake_files.inputs, |inputs| {
+        for input in inputs {
+            println!("{}", input.path.display());
+        }
+    })
+}
+
+fn index(reader: &reply::Reader, options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let cmake = &reader.index().cmake;
+    output(options, cmake, |cmake| {
+        println!("version: {}", cmake.version.string);
+        println!("generator: {}", cmake.generator.name);
+        if let Some(platform) = &cmake.generator.platform {
+            println!("platform: {platform}");
+        }
+        println!("multi-config: {}", cmake.generator.multi_config);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| (*arg).to_owned()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse(&[
+            "-B",
+            "build",
+            "--config",
+            "Release",
+            "--json",
+            "targets",
+            "--type",
+            "EXECUTABLE",
+        ])
+        .unwrap();
+        assert_eq!(options.build_dir, PathBuf::from("build"));
+        assert_eq!(options.config.as_deref(), Some("Release"));
+        assert!(options.json);
+        assert_eq!(options.type_filter.as_deref(), Some("EXECUTABLE"));
+        assert_eq!(options.command, vec!["targets"]);
+
+        let defaults = parse(&["cache", "get", "CMAKE_BUILD_TYPE"]).unwrap();
+        assert_eq!(defaults.build_dir, PathBuf::from("."));
+        assert!(!defaults.json);
+        assert_eq!(defaults.command, vec!["cache", "get", "CMAKE_BUILD_TYPE"]);
+
+        assert_eq!(parse(&["--help"]).unwrap().command, vec!["help"]);
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn test_compile_rows() {
+        let mut codemodel = objects::CodeModelV2::default();
+        codemodel.paths.source = "/project".into();
+        let mut debug = Configuration::default();
+        debug.name = "Debug".into();
+        debug.targets = vec![serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@1",
+            "type": "EXECUTABLE",
+            "paths": { "build": ".", "source": "." },
+            "sources": [ { "path": "src/main.cpp", "compileGroupIndex": 0 } ],
+            "compileGroups": [ { "language": "CXX", "sourceIndexes": [ 0 ] } ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap()];
+        codemodel.configurations = vec![debug];
+        let config = &codemodel.configurations[0];
+
+        let rows = |file: &str, cwd: &str| {
+            compile_rows(&codemodel, config, Path::new(file), Path::new(cwd))
+        };
+        assert_eq!(rows("./src/main.cpp", "/project").len(), 1);
+        assert_eq!(rows("src/../src/main.cpp", "/project").len(), 1);
+        assert_eq!(rows("main.cpp", "/project/src").len(), 1);
+        assert_eq!(rows("/project/src/./main.cpp", "/elsewhere").len(), 1);
+        // relative to the top-level source directory
+        assert_eq!(rows("src/main.cpp", "/elsewhere").len(), 1);
+        assert!(rows("src/other.cpp", "/project").is_empty());
+    }
+}
@@ -0,0 +1,648 @@
+//! Helpers for cargo build scripts that link a `CMake`-built C/C++ target
+//!
+//! Requires the `build-script` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use cmake_file_api::{build_script, reply};
+//!
+//! let reader = reply::Reader::from_build_dir("path/to/build")?;
+//! build_script::Linker::new(&reader)
+//!     .configuration("Release")
+//!     .link("mylib")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::objects::codemodel_v2::{
+    CommandLineStyle, Configuration, LinkFragments, LinkLibrary, SplitError, Target,
+};
+use crate::objects::{CMakeFilesV1, CodeModelV2, ToolchainsV1};
+use crate::paths;
+use crate::reply;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Errors for generating build script directives
+#[non_exhaustive]
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read reply: {0}")]
+    Reader(reply::ReaderError),
+
+    #[error("configuration not found: {0}")]
+    ConfigurationNotFound(String),
+
+    #[error("target not found: {0}")]
+    TargetNotFound(String),
+
+    #[error("Failed to split link command: {0}")]
+    Split(SplitError),
+}
+
+impl From<reply::ReaderError> for Error {
+    fn from(err: reply::ReaderError) -> Self {
+        Error::Reader(err)
+    }
+}
+
+impl From<SplitError> for Error {
+    fn from(err: SplitError) -> Self {
+        Error::Split(err)
+    }
+}
+
+/// Kind of a library passed to `cargo:rustc-link-lib`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LibKind {
+    Static,
+    Dylib,
+    Framework,
+}
+
+impl LibKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LibKind::Static => "static",
+            LibKind::Dylib => "dylib",
+            LibKind::Framework => "framework",
+        }
+    }
+}
+
+/// A single `cargo:` build script instruction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Directive {
+    /// `cargo:rustc-link-search=native=<path>`
+    LinkSearch(PathBuf),
+
+    /// `cargo:rustc-link-search=framework=<path>`
+    FrameworkSearch(PathBuf),
+
+    /// `cargo:rustc-link-lib=<kind>=<name>`
+    LinkLib(LibKind, String),
+
+    /// `cargo:rerun-if-changed=<path>`
+    RerunIfChanged(PathBuf),
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Directive::LinkSearch(path) => {
+                write!(f, "cargo:rustc-link-search=native={}", path.display())
+            }
+            Directive::FrameworkSearch(path) => {
+                write!(f, "cargo:rustc-link-search=framework={}", path.display())
+            }
+            Directive::LinkLib(kind, name) => {
+                write!(f, "cargo:rustc-link-lib={}={name}", kind.as_str())
+            }
+            Directive::RerunIfChanged(path) => {
+                write!(f, "cargo:rerun-if-changed={}", path.display())
+            }
+        }
+    }
+}
+
+/// Generates cargo link instructions for a `CMake` target
+pub struct Linker<'a> {
+    reader: &'a reply::Reader,
+    configuration: Option<String>,
+}
+
+impl<'a> Linker<'a> {
+    #[must_use]
+    pub fn new(reader: &'a reply::Reader) -> Self {
+        Linker {
+            reader,
+            configuration: None,
+        }
+    }
+
+    /// Select the configuration for multi-config generators (defaults to the first configuration)
+    pub fn configuration(&mut self, name: &str) -> &mut Self {
+        self.configuration = Some(name.to_owned());
+        self
+    }
+
+    /// Cargo instructions to link a target and its transitive static dependencies
+    ///
+    /// Dependencies of shared libraries are not linked again, only the library itself is.
+    ///
+    /// # Errors
+    ///
+    /// `Error::Reader`: if the codemodel, cmakeFiles or toolchains object can not be read
+    /// `Error::ConfigurationNotFound`: if the selected configuration does not exist
+    /// `Error::TargetNotFound`: if no target with the given name exists
+    /// `Error::Split`: if a link command fragment can not be split into arguments
+    pub fn directives(&self, target_name: &str) -> Result<Vec<Directive>, Error> {
+        let codemodel: CodeModelV2 = self.reader.read_object()?;
+        let cmake_files: Option<CMakeFilesV1> = if self.reader.has_object::<CMakeFilesV1>() {
+            Some(self.reader.read_object()?)
+        } else {
+            None
+        };
+        let toolchains: Option<ToolchainsV1> = if self.reader.has_object::<ToolchainsV1>() {
+            Some(self.reader.read_object()?)
+        } else {
+            None
+        };
+
+        directives(
+            &codemodel,
+            cmake_files.as_ref(),
+            toolchains.as_ref(),
+            self.configuration.as_deref(),
+            target_name,
+        )
+    }
+
+    /// Print cargo instructions to link a target to stdout
+    ///
+    /// # Errors
+    ///
+    /// see [`Linker::directives`]
+    pub fn link(&self, target_name: &str) -> Result<(), Error> {
+        for directive in self.directives(target_name)? {
+            println!("{directive}");
+        }
+        Ok(())
+    }
+}
+
+fn directives(
+    codemodel: &CodeModelV2,
+    cmake_files: Option<&CMakeFilesV1>,
+    toolchains: Option<&ToolchainsV1>,
+    configuration: Option<&str>,
+    target_name: &str,
+) -> Result<Vec<Directive>, Error> {
+    let config = match configuration {
+        Some(name) => codemodel
+            .configurations
+            .iter()
+            .find(|config| config.name == name)
+            .ok_or_else(|| Error::ConfigurationNotFound(name.to_owned()))?,
+        None => codemodel
+            .configurations
+            .first()
+            .ok_or_else(|| Error::ConfigurationNotFound(String::new()))?,
+    };
+
+    let root = config
+        .targets
+        .iter()
+        .find(|target| target.name == target_name)
+        .ok_or_else(|| Error::TargetNotFound(target_name.to_owned()))?;
+
+    let build_dir = &codemodel.paths.build;
+    let mut directives = Directives::default();
+
+    let order = link_order(config, root);
+    for target in &order {
+        if let Some((kind, path)) = library_artifact(target) {
+            directives.push_library(kind, &build_dir.join(path));
+        }
+    }
+
+    let project_artifacts: HashSet<String> = config
+        .targets
+        .iter()
+        .flat_map(|target| &target.artifacts)
+        .map(|artifact| paths::resolve(build_dir, &artifact.path))
+        .collect();
+
+    // libraries on the link line of a linked root are already part of its artifact
+    let external = if root.link.is_some() {
+        Vec::new()
+    } else {
+        interface_link_fragments(config, &order, toolchains, build_dir)?
+    };
+    for fragments in external {
+        for path in fragments.library_paths {
+            directives.push(Directive::LinkSearch(build_dir.join(path)));
+        }
+        for path in fragments.framework_paths {
+            directives.push(Directive::FrameworkSearch(build_dir.join(path)));
+        }
+        for library in fragments.libraries {
+            match library {
+                LinkLibrary::Name(name) => {
+                    directives.push(Directive::LinkLib(LibKind::Dylib, name));
+                }
+                LinkLibrary::Framework(name) => {
+                    directives.push(Directive::LinkLib(LibKind::Framework, name));
+                }
+                // project libraries are covered by the link order
+                LinkLibrary::Path(path)
+                    if !project_artifacts.contains(&paths::resolve(build_dir, &path)) =>
+                {
+                    directives.push_library(library_kind(&path), &build_dir.join(&path));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(files) = cmake_files {
+        for input in &files.inputs {
+            if !input.is_cmake && !input.is_generated {
+                directives.push(Directive::RerunIfChanged(
+                    files.paths.source.join(&input.path),
+                ));
+            }
+        }
+    }
+
+    Ok(directives.entries)
+}
+
+/// Directives without duplicates, preserving the order of first occurrence
+#[derive(Default)]
+struct Directives {
+    entries: Vec<Directive>,
+    seen: HashSet<Directive>,
+}
+
+impl Directives {
+    fn push(&mut self, directive: Directive) {
+        if self.seen.insert(directive.clone()) {
+            self.entries.push(directive);
+        }
+    }
+
+    /// Search path and name of a library file
+    fn push_library(&mut self, kind: LibKind, path: &Path) {
+        if let (Some(dir), Some(name)) = (path.parent(), library_name(path)) {
+            self.push(Directive::LinkSearch(dir.to_path_buf()));
+            self.push(Directive::LinkLib(kind, name));
+        }
+    }
+}
+
+/// The root target followed by its transitive static dependencies, dependents before dependencies.
+///
+/// Only dependencies of static and object libraries are followed, linked targets like shared libraries
+/// already contain their dependencies.
+fn link_order<'a>(config: &'a Configuration, root: &'a Target) -> Vec<&'a Target> {
+    fn visit<'a>(
+        config: &'a Configuration,
+        target: &'a Target,
+        visited: &mut HashSet<&'a str>,
+        post_order: &mut Vec<&'a Target>,
+    ) {
+        if !visited.insert(target.id.as_str()) {
+            return;
+        }
+
+        if matches!(
+            target.type_name.as_str(),
+            "STATIC_LIBRARY" | "OBJECT_LIBRARY"
+        ) {
+            for dependency in &target.dependencies {
+                if let Some(dependency_target) =
+                    config.targets.iter().find(|t| t.id == dependency.id)
+                {
+                    visit(config, dependency_target, visited, post_order);
+                }
+            }
+        }
+
+        post_order.push(target);
+    }
+
+    let mut visited = HashSet::new();
+    let mut post_order = Vec::new();
+    visit(config, root, &mut visited, &mut post_order);
+    post_order.reverse();
+    post_order
+}
+
+/// Link fragments of the consumers of the static libraries in `order`, starting at the first static library
+///
+/// Static libraries have no link step, so their usage requirements (e.g. `-lpthread`) only show up
+/// on the link lines of the targets linking them, following the library file.
+fn interface_link_fragments(
+    config: &Configuration,
+    order: &[&Target],
+    toolchains: Option<&ToolchainsV1>,
+    build_dir: &Path,
+) -> Result<Vec<LinkFragments>, SplitError> {
+    let static_libraries: HashSet<String> = order
+        .iter()
+        .filter(|target| target.type_name == "STATIC_LIBRARY")
+        .flat_map(|target| &target.artifacts)
+        .map(|artifact| paths::resolve(build_dir, &artifact.path))
+        .collect();
+
+    let mut result = Vec::new();
+    for link in config
+        .targets
+        .iter()
+        .filter_map(|target| target.link.as_ref())
+    {
+        let mut fragments =
+            link.fragments(CommandLineStyle::for_language(toolchains, &link.language))?;
+        let start = fragments
+            .libraries
+            .iter()
+            .position(|library| match library {
+                LinkLibrary::Path(path) => {
+                    static_libraries.contains(&paths::resolve(build_dir, path))
+                }
+                _ => false,
+            });
+        if let Some(first) = start {
+            fragments.libraries.drain(..first);
+            result.push(fragments);
+        }
+    }
+    Ok(result)
+}
+
+/// The artifact to link against and its kind
+fn library_artifact(target: &Target) -> Option<(LibKind, &Path)> {
+    let artifacts = target
+        .artifacts
+        .iter()
+        .map(|artifact| artifact.path.as_path());
+    match target.type_name.as_str() {
+        "STATIC_LIBRARY" => artifacts.map(|path| (LibKind::Static, path)).next(),
+        "SHARED_LIBRARY" => {
+            let paths: Vec<&Path> = artifacts.collect();
+            // prefer the import library on Windows
+            paths
+                .iter()
+                .find(|path| has_extension(path, "lib"))
+                .or_else(|| paths.first())
+                .map(|path| (LibKind::Dylib, *path))
+        }
+        _ => None,
+    }
+}
+
+/// Kind of an external library file
+///
+/// `.lib` files can be static or import libraries. rustc passes both kinds to the MSVC linker the same way,
+/// but only bundles static ones, so they are linked as `dylib` to keep import libraries working.
+fn library_kind(path: &Path) -> LibKind {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if file_name.ends_with(".a") && !file_name.ends_with(".dll.a") {
+        LibKind::Static
+    } else {
+        LibKind::Dylib
+    }
+}
+
+/// Library name without the `lib` prefix and the library suffix, e.g. `libfoo.so.1` -> `foo`
+fn library_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let lowercase = file_name.to_ascii_lowercase();
+
+    let stem = if let Some(index) = lowercase.find(".so.") {
+        &file_name[..index]
+    } else {
+        [".dll.a", ".a", ".so", ".dylib", ".tbd", ".lib"]
+            .iter()
+            .find(|suffix| lowercase.ends_with(*suffix))
+            .map_or(file_name, |suffix| {
+                &file_name[..file_name.len() - suffix.len()]
+            })
+    };
+
+    let name = if lowercase.ends_with(".lib") {
+        stem
+    } else {
+        stem.strip_prefix("lib").unwrap_or(stem)
+    };
+    Some(name.to_owned())
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .map_or(false, |ext| ext.eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::cmake_files_v1::Input;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_directives() {
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![
+                target(json!({
+                    "name": "core",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "core/libcore.a" } ],
+                })),
+                target(json!({
+                    "name": "util",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "util/libutil.a" } ],
+                    "dependencies": [ { "id": "core::@1" } ],
+                })),
+                target(json!({
+                    "name": "shared",
+                    "type": "SHARED_LIBRARY",
+                    "artifacts": [ { "path": "shared/libshared.so.1" } ],
+                    "dependencies": [ { "id": "core::@1" } ],
+                })),
+                target(json!({
+                    "name": "api",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "libapi.a" } ],
+                    "dependencies": [ { "id": "core::@1" }, { "id": "util::@1" }, { "id": "shared::@1" } ],
+                })),
+            ],
+            ..Default::default()
+        });
+
+        let mut cmake_files = CMakeFilesV1::default();
+        cmake_files.paths.source = "/src".into();
+        cmake_files.inputs = vec![
+            Input {
+                path: "CMakeLists.txt".into(),
+                ..Default::default()
+            },
+            Input {
+                path: "/usr/share/cmake/Modules/CMakeCInformation.cmake".into(),
+                is_cmake: true,
+                ..Default::default()
+            },
+        ];
+
+        let lines: Vec<String> = directives(&codemodel, Some(&cmake_files), None, None, "api")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "cargo:rustc-link-search=native=/build",
+                "cargo:rustc-link-lib=static=api",
+                "cargo:rustc-link-search=native=/build/shared",
+                "cargo:rustc-link-lib=dylib=shared",
+                "cargo:rustc-link-search=native=/build/util",
+                "cargo:rustc-link-lib=static=util",
+                "cargo:rustc-link-search=native=/build/core",
+                "cargo:rustc-link-lib=static=core",
+                "cargo:rerun-if-changed=/src/CMakeLists.txt",
+            ]
+        );
+
+        assert!(matches!(
+            directives(&codemodel, None, None, Some("Release"), "api"),
+            Err(Error::ConfigurationNotFound(_))
+        ));
+        assert!(matches!(
+            directives(&codemodel, None, None, None, "missing"),
+            Err(Error::TargetNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_static_library_root() {
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            targets: [
+                json!({
+                    "name": "core",
+                    "id": "core::@1",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "core/libcore.a" } ],
+                }),
+                json!({
+                    "name": "api",
+                    "id": "api::@1",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "libapi.a" } ],
+                    "dependencies": [ { "id": "core::@1" } ],
+                }),
+                json!({
+                    "name": "app",
+                    "id": "app::@1",
+                    "type": "EXECUTABLE",
+                    "artifacts": [ { "path": "app" } ],
+                    "dependencies": [ { "id": "api::@1" } ],
+                    "link": {
+                        "language": "CXX",
+                        "commandFragments": [
+                            { "fragment": "-lm", "role": "libraries" },
+                            { "fragment": "-L/opt/z/lib", "role": "libraryPath" },
+                            { "fragment": "libapi.a", "role": "libraries" },
+                            { "fragment": "core/libcore.a", "role": "libraries" },
+                            { "fragment": "-lpthread", "role": "libraries" },
+                            { "fragment": "/opt/z/lib/libz.bar.a", "role": "libraries" },
+                        ],
+                    },
+                }),
+            ]
+            .into_iter()
+            .map(target)
+            .collect(),
+            ..Default::default()
+        });
+
+        let lines: Vec<String> = directives(&codemodel, None, None, None, "api")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "cargo:rustc-link-search=native=/build",
+                "cargo:rustc-link-lib=static=api",
+                "cargo:rustc-link-search=native=/build/core",
+                "cargo:rustc-link-lib=static=core",
+                "cargo:rustc-link-search=native=/opt/z/lib",
+                "cargo:rustc-link-lib=dylib=pthread",
+                "cargo:rustc-link-lib=static=z.bar",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shared_library_root() {
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            targets: [
+                json!({
+                    "name": "core",
+                    "id": "core::@1",
+                    "type": "STATIC_LIBRARY",
+                    "artifacts": [ { "path": "core/libcore.a" } ],
+                }),
+                json!({
+                    "name": "shared",
+                    "id": "shared::@1",
+                    "type": "SHARED_LIBRARY",
+                    "artifacts": [ { "path": "shared/libshared.so" } ],
+                    "dependencies": [ { "id": "core::@1" } ],
+                    "link": {
+                        "language": "CXX",
+                        "commandFragments": [
+                            { "fragment": "-L/opt/z/lib", "role": "libraryPath" },
+                            { "fragment": "../core/libcore.a", "role": "libraries" },
+                            { "fragment": "-lz", "role": "libraries" },
+                        ],
+                    },
+                }),
+            ]
+            .into_iter()
+            .map(target)
+            .collect(),
+            ..Default::default()
+        });
+
+        let lines: Vec<String> = directives(&codemodel, None, None, None, "shared")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "cargo:rustc-link-search=native=/build/shared",
+                "cargo:rustc-link-lib=dylib=shared",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_library_name() {
+        let name = |path: &str| library_name(Path::new(path)).unwrap();
+        assert_eq!(name("libfoo.bar.a"), "foo.bar");
+        assert_eq!(name("libfoo.so.1.2"), "foo");
+        assert_eq!(name("libfoo.dll.a"), "foo");
+        assert_eq!(name("foo.bar.lib"), "foo.bar");
+        assert_eq!(name("libfoo.dylib"), "foo");
+
+        assert_eq!(library_kind(Path::new("C:/sdk/foo.lib")), LibKind::Dylib);
+        assert_eq!(library_kind(Path::new("libfoo.dll.a")), LibKind::Dylib);
+        assert_eq!(library_kind(Path::new("libfoo.a")), LibKind::Static);
+    }
+}
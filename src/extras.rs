@@ -0,0 +1,545 @@
+//! Forward-compatible handling of JSON fields that are not modeled by this crate
+//!
+//! Unknown fields are ignored by default. With the `extras` feature every reply struct has an `extras` member which captures unknown fields,
+//! e.g. fields added by a newer `CMake` version, so serializing a reply again is lossless.
+//!
+//! Independent of the feature, [`unknown_fields`] reports every field that the typed model does not know,
+//! which is useful for conformance tests against new `CMake` versions (strict mode).
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Unknown JSON fields of a struct, by field name
+pub type Extras = serde_json::Map<String, Value>;
+
+/// Name of the newtype wrapping every unknown field value during serialization
+#[cfg(feature = "extras")]
+const EXTRA_FIELD: &str = "$cmake_file_api::extras::Extra";
+
+/// `serialize_with` function of the extras members
+///
+/// Values are wrapped in a marker newtype, which JSON serializers write transparently, so that
+/// [`unknown_fields`] can tell them apart from the modeled fields.
+#[cfg(feature = "extras")]
+pub(crate) fn serialize<S: serde::Serializer>(
+    extras: &Extras,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+
+    struct Extra<'a>(&'a Value);
+
+    impl Serialize for Extra<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct(EXTRA_FIELD, self.0)
+        }
+    }
+
+    let mut map = serializer.serialize_map(Some(extras.len()))?;
+    for (key, value) in extras {
+        map.serialize_entry(key, &Extra(value))?;
+    }
+    map.end()
+}
+
+/// A JSON field which is not part of the typed model.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UnknownField {
+    /// Reply file containing the field, relative to the reply directory.
+    pub file: PathBuf,
+
+    /// JSON pointer to the field, e.g. `/configurations/0/newField`.
+    pub pointer: String,
+}
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.pointer)
+    }
+}
+
+/// Report the fields of a JSON value which are not part of the typed model `T`
+///
+/// Returns JSON pointers to the unknown fields. Nested values of unknown fields are not reported separately.
+///
+/// # Errors
+///
+/// `serde_json::Error`: if the value can not be deserialized into `T`
+pub fn unknown_fields<T: Serialize + DeserializeOwned>(
+    json: &Value,
+) -> Result<Vec<String>, serde_json::Error> {
+    let object = T::deserialize(json)?;
+
+    // without the `extras` feature unknown fields are dropped while deserializing
+    #[allow(unused_mut)]
+    let mut known = serde_json::to_value(&object)?;
+    #[cfg(feature = "extras")]
+    {
+        let mut extras = Vec::new();
+        object.serialize(locate::ExtrasLocator {
+            pointer: String::new(),
+            extras: &mut extras,
+        })?;
+        for pointer in extras {
+            remove_pointer(&mut known, &pointer);
+        }
+    }
+
+    let mut unknown = Vec::new();
+    collect_unknown("", json, &known, &mut unknown);
+    Ok(unknown)
+}
+
+fn collect_unknown(pointer: &str, json: &Value, known: &Value, unknown: &mut Vec<String>) {
+    match (json, known) {
+        (Value::Object(fields), Value::Object(known_fields)) => {
+            for (key, value) in fields {
+                let child = child_pointer(pointer, key);
+                match known_fields.get(key) {
+                    Some(known_value) => collect_unknown(&child, value, known_value, unknown),
+                    None => unknown.push(child),
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(known_items)) => {
+            for (index, (item, known_item)) in items.iter().zip(known_items).enumerate() {
+                collect_unknown(&format!("{pointer}/{index}"), item, known_item, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(feature = "extras")]
+fn remove_pointer(value: &mut Value, pointer: &str) {
+    let (parent, key) = match pointer.rsplit_once('/') {
+        Some(split) => split,
+        None => return,
+    };
+    if let Some(Value::Object(fields)) = value.pointer_mut(parent) {
+        fields.remove(&key.replace("~1", "/").replace("~0", "~"));
+    }
+}
+
+/// Serializer pass collecting the JSON pointers of the extras entries
+#[cfg(feature = "extras")]
+mod locate {
+    use super::{child_pointer, EXTRA_FIELD};
+    use serde::ser::{self, Serialize};
+    use serde_json::Value;
+
+    pub(super) struct ExtrasLocator<'a> {
+        pub(super) pointer: String,
+        pub(super) extras: &'a mut Vec<String>,
+    }
+
+    impl<'a> ExtrasLocator<'a> {
+        fn child(&mut self, key: &str) -> ExtrasLocator<'_> {
+            ExtrasLocator {
+                pointer: child_pointer(&self.pointer, key),
+                extras: self.extras,
+            }
+        }
+
+        fn compound(self) -> Compound<'a> {
+            Compound {
+                locator: self,
+                next_index: 0,
+                key: String::new(),
+            }
+        }
+    }
+
+    pub(super) struct Compound<'a> {
+        locator: ExtrasLocator<'a>,
+        next_index: usize,
+        key: String,
+    }
+
+    impl Compound<'_> {
+        fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+            let index = self.next_index.to_string();
+            self.next_index += 1;
+            value.serialize(self.locator.child(&index))
+        }
+
+        fn field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &str,
+            value: &T,
+        ) -> Result<(), serde_json::Error> {
+            value.serialize(self.locator.child(key))
+        }
+    }
+
+    impl<'a> ser::Serializer for ExtrasLocator<'a> {
+        type Ok = ();
+        type Error = serde_json::Error;
+        type SerializeSeq = Compound<'a>;
+        type SerializeTuple = Compound<'a>;
+        type SerializeTupleStruct = Compound<'a>;
+        type SerializeTupleVariant = Compound<'a>;
+        type SerializeMap = Compound<'a>;
+        type SerializeStruct = Compound<'a>;
+        type SerializeStructVariant = Compound<'a>;
+
+        fn serialize_bool(self, _: bool) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i8(self, _: i8) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i16(self, _: i16) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i32(self, _: i32) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i64(self, _: i64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u8(self, _: u8) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u16(self, _: u16) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u32(self, _: u32) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u64(self, _: u64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_f32(self, _: f32) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_f64(self, _: f64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_char(self, _: char) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_str(self, _: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_bytes(self, _: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_none(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Self::Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            name: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            if name == EXTRA_FIELD {
+                self.extras.push(self.pointer);
+                Ok(())
+            } else {
+                value.serialize(self)
+            }
+        }
+
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            mut self,
+            _: &'static str,
+            _: u32,
+            variant: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            value.serialize(self.child(variant))
+        }
+
+        fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'a>, Self::Error> {
+            Ok(self.compound())
+        }
+
+        fn serialize_tuple(self, _: usize) -> Result<Compound<'a>, Self::Error> {
+            Ok(self.compound())
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Compound<'a>, Self::Error> {
+            Ok(self.compound())
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            variant: &'static str,
+            _: usize,
+        ) -> Result<Compound<'a>, Self::Error> {
+            let pointer = child_pointer(&self.pointer, variant);
+            Ok(ExtrasLocator {
+                pointer,
+                extras: self.extras,
+            }
+            .compound())
+        }
+
+        fn serialize_map(self, _: Option<usize>) -> Result<Compound<'a>, Self::Error> {
+            Ok(self.compound())
+        }
+
+        fn serialize_struct(self, _: &'static str, _: usize) -> Result<Compound<'a>, Self::Error> {
+            Ok(self.compound())
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            variant: &'static str,
+            _: usize,
+        ) -> Result<Compound<'a>, Self::Error> {
+            let pointer = child_pointer(&self.pointer, variant);
+            Ok(ExtrasLocator {
+                pointer,
+                extras: self.extras,
+            }
+            .compound())
+        }
+    }
+
+    impl ser::SerializeSeq for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_element<T: ?Sized + Serialize>(
+            &mut self,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.element(value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeTuple for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_element<T: ?Sized + Serialize>(
+            &mut self,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.element(value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeTupleStruct for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.element(value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeTupleVariant for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.element(value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeMap for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+            // map keys are strings or numbers written as strings in JSON
+            self.key = match serde_json::to_value(key)? {
+                Value::String(name) => name,
+                other => other.to_string(),
+            };
+            Ok(())
+        }
+
+        fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+            let key = std::mem::take(&mut self.key);
+            self.field(&key, value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeStruct for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.field(key, value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeStructVariant for Compound<'_> {
+        type Ok = ();
+        type Error = serde_json::Error;
+
+        fn serialize_field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error> {
+            self.field(key, value)
+        }
+
+        fn end(self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extras::*;
+    use crate::index::Index;
+    use crate::objects::codemodel_v2::Target;
+    use crate::reply::snapshot::Snapshot;
+    use crate::reply::Reader;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_unknown_fields() {
+        let json = json!({
+            "name": "app",
+            "id": "app::@1",
+            "type": "EXECUTABLE",
+            "paths": { "source": ".", "build": "." },
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] },
+            "sources": [
+                { "path": "main.cpp", "isModuleInterface": true },
+                { "path": "util.cpp" }
+            ],
+            "newTargetField": { "nested": 1 }
+        });
+
+        assert_eq!(
+            unknown_fields::<Target>(&json).unwrap(),
+            vec!["/newTargetField", "/sources/0/isModuleInterface"]
+        );
+
+        #[cfg(feature = "extras")]
+        {
+            let target: Target = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(target.extras["newTargetField"], json!({ "nested": 1 }));
+            assert_eq!(target.sources[0].extras["isModuleInterface"], json!(true));
+            assert!(target.sources[1].extras.is_empty());
+
+            let round_trip = serde_json::to_value(&target).unwrap();
+            assert_eq!(round_trip["newTargetField"], json["newTargetField"]);
+            assert_eq!(round_trip["sources"][0]["isModuleInterface"], json!(true));
+        }
+
+        let index: Index = serde_json::from_value(json!({
+            "cmake": {
+                "version": { "major": 3, "minor": 30, "patch": 0, "suffix": "", "string": "3.30.0", "isDirty": false },
+                "paths": { "cmake": "cmake", "ctest": "ctest", "cpack": "cpack", "root": "share" },
+                "generator": { "multiConfig": false, "name": "Ninja" }
+            },
+            "objects": [
+                { "kind": "toolchains", "version": { "major": 1, "minor": 0 }, "jsonFile": "toolchains-v1.json" }
+            ],
+            "reply": {}
+        }))
+        .unwrap();
+        let reader = Reader::from_snapshot(Snapshot {
+            format_version: Snapshot::FORMAT_VERSION,
+            source_dir: None,
+            build_dir: "build".into(),
+            index,
+            files: BTreeMap::from([(
+                "toolchains-v1.json".to_owned(),
+                json!({
+                    "kind": "toolchains",
+                    "version": { "major": 1, "minor": 0 },
+                    "toolchains": [{ "language": "CXX", "compiler": { "implicit": {} }, "linker": {} }]
+                }),
+            )]),
+        });
+        let unknown = reader.unknown_fields().unwrap();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(
+            unknown[0].to_string(),
+            "toolchains-v1.json: /toolchains/0/linker"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Generators for IDE configuration files based on the cmake-file-api
+//!
+//! The generators only need reply objects, so they also work in environments where
+//! no `CMake` integration for the IDE is available.
+
+pub mod clangd;
+pub mod vscode;
@@ -0,0 +1,330 @@
+//! clangd `.clangd` configuration generation
+//!
+//! The generated file points clangd to the compilation database of the build directory and
+//! adds one path-matched fragment per source directory that removes flags clangd can not handle
+//! and adds the implicit include directories of the toolchains.
+
+use crate::objects::codemodel_v2::{CodeModel, Target};
+use crate::objects::toolchains_v1::Toolchains;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Content of a `.clangd` file
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ClangdConfig {
+    /// Directory containing `compile_commands.json`, i.e. the build directory.
+    pub compilation_database: String,
+
+    /// Path-matched fragments, one per source directory with compiled sources.
+    pub fragments: Vec<ClangdFragment>,
+}
+
+/// A path-matched `.clangd` fragment
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ClangdFragment {
+    /// Source directory relative to the top-level source directory (`.` for the top-level directory).
+    pub directory: String,
+
+    /// `If.PathMatch` regular expression matching the files of the directory.
+    pub path_match: String,
+
+    /// `CompileFlags.Add`
+    pub add: Vec<String>,
+
+    /// `CompileFlags.Remove`
+    pub remove: Vec<String>,
+}
+
+/// Generate a `.clangd` configuration for the top-level source directory
+///
+/// Only the first configuration is considered since `compile_commands.json` is written for a single configuration.
+/// Without toolchains no implicit include directories are added.
+#[must_use]
+pub fn clangd_config(codemodel: &CodeModel, toolchains: Option<&Toolchains>) -> ClangdConfig {
+    let mut config = ClangdConfig {
+        compilation_database: paths::normalize(&codemodel.paths.build),
+        fragments: Vec::new(),
+    };
+
+    let targets = codemodel
+        .configurations
+        .first()
+        .map_or(&[][..], |configuration| &configuration.targets[..]);
+    for target in targets {
+        add_target(&mut config, codemodel, toolchains, target);
+    }
+
+    config
+        .fragments
+        .retain(|fragment| !fragment.add.is_empty() || !fragment.remove.is_empty());
+    config
+}
+
+/// Write the `.clangd` file into `dir`, usually the top-level source directory
+///
+/// # Errors
+///
+/// `std::io::Error`: if the file can not be written
+pub fn write<P: AsRef<Path>>(
+    dir: P,
+    codemodel: &CodeModel,
+    toolchains: Option<&Toolchains>,
+) -> io::Result<()> {
+    fs::write(
+        dir.as_ref().join(".clangd"),
+        clangd_config(codemodel, toolchains).to_string(),
+    )
+}
+
+fn add_target(
+    config: &mut ClangdConfig,
+    codemodel: &CodeModel,
+    toolchains: Option<&Toolchains>,
+    target: &Target,
+) {
+    let source_root = paths::normalize(&codemodel.paths.source);
+    for group in &target.compile_groups {
+        let remove: Vec<&str> = group
+            .compile_fragments()
+            .iter()
+            .filter_map(|flag| unsupported_flag(flag))
+            .collect();
+        let implicit_includes = toolchains
+            .and_then(|available| available.toolchain(&group.language))
+            .map_or(&[][..], |toolchain| {
+                &toolchain.compiler.implicit.include_directories[..]
+            });
+
+        for source in group
+            .source_indexes
+            .iter()
+            .filter_map(|&index| target.source(index))
+        {
+            // sources outside of the source tree can not be matched by a `.clangd` in the source directory
+            let source_path = paths::resolve(&codemodel.paths.source, &source.path);
+            if !paths::starts_with(&source_path, &source_root) {
+                continue;
+            }
+            let relative = source_path[source_root.len()..].trim_start_matches('/');
+            let directory = relative.rsplit_once('/').map_or(".", |(dir, _)| dir);
+
+            let fragment = fragment_for(config, directory);
+            for flag in &remove {
+                if !fragment.remove.iter().any(|existing| existing == flag) {
+                    fragment.remove.push((*flag).to_owned());
+                }
+            }
+            for include in implicit_includes {
+                let include_dir = paths::normalize(include);
+                if !fragment
+                    .add
+                    .chunks(2)
+                    .any(|pair| pair.get(1) == Some(&include_dir))
+                {
+                    fragment.add.push("-isystem".to_owned());
+                    fragment.add.push(include_dir);
+                }
+            }
+        }
+    }
+}
+
+fn fragment_for<'a>(config: &'a mut ClangdConfig, directory: &str) -> &'a mut ClangdFragment {
+    let position = config
+        .fragments
+        .iter()
+        .position(|fragment| fragment.directory == directory)
+        .unwrap_or_else(|| {
+            let prefix = if directory == "." {
+                String::new()
+            } else {
+                format!("{}/", escape_regex(directory))
+            };
+            config.fragments.push(ClangdFragment {
+                directory: directory.to_owned(),
+                path_match: format!("{prefix}[^/]*"),
+                ..Default::default()
+            });
+            config.fragments.len() - 1
+        });
+    &mut config.fragments[position]
+}
+
+/// `CompileFlags.Remove` pattern for flags clangd can not handle
+fn unsupported_flag(flag: &str) -> Option<&'static str> {
+    const PATTERNS: &[&str] = &[
+        // MSVC parallel builds and precompiled headers
+        "/MP*",
+        "-MP*",
+        "/Yc*",
+        "-Yc*",
+        "/Yu*",
+        "-Yu*",
+        "/Fp*",
+        "-Fp*",
+        // clang precompiled headers
+        "-include-pch",
+        "-Winvalid-pch",
+        // GCC only flags
+        "-fno-semantic-interposition",
+        "-fstrict-volatile-bitfields",
+        "-fno-var-tracking-assignments",
+        "-fmodules-ts",
+        "-fmodule-mapper=*",
+        "-fdeps-format=*",
+        "-fdeps-file=*",
+        "-fdeps-target=*",
+    ];
+
+    PATTERNS.iter().copied().find(|pattern| {
+        pattern
+            .strip_suffix('*')
+            .map_or(flag == *pattern, |prefix| {
+                // `-MP` is also a GCC dependency flag, only the MSVC spelling is removed
+                flag.starts_with(prefix)
+                    && (prefix != "-MP" || flag[prefix.len()..].parse::<u32>().is_ok())
+            })
+    })
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Single quoted YAML scalar
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, key: &str, values: &[String]) -> fmt::Result {
+    if values.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "  {key}:")?;
+    for value in values {
+        writeln!(f, "    - {}", quote(value))?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ClangdConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "CompileFlags:")?;
+        writeln!(
+            f,
+            "  CompilationDatabase: {}",
+            quote(&self.compilation_database)
+        )?;
+
+        for fragment in &self.fragments {
+            writeln!(f, "---")?;
+            writeln!(f, "If:")?;
+            writeln!(f, "  PathMatch: {}", quote(&fragment.path_match))?;
+            writeln!(f, "CompileFlags:")?;
+            write_list(f, "Add", &fragment.add)?;
+            write_list(f, "Remove", &fragment.remove)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ide::clangd::*;
+    use crate::objects::codemodel_v2::Configuration;
+    use serde_json::json;
+
+    #[test]
+    fn test_clangd() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "directoryIndex": 0,
+            "projectIndex": 0,
+            "paths": { "source": ".", "build": "." },
+            "sources": [
+                { "path": "src/main.cpp", "compileGroupIndex": 0 },
+                { "path": "src/util.cpp", "compileGroupIndex": 0 },
+                { "path": "main.c", "compileGroupIndex": 1 },
+                { "path": "/opt/external/lib.cpp", "compileGroupIndex": 0 }
+            ],
+            "compileGroups": [
+                {
+                    "language": "CXX",
+                    "sourceIndexes": [0, 1, 3],
+                    "compileCommandFragments": [
+                        { "fragment": "-O2 -fno-semantic-interposition /MP8 -MP" }
+                    ]
+                },
+                {
+                    "language": "C",
+                    "sourceIndexes": [2]
+                }
+            ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+
+        let toolchains: Toolchains = serde_json::from_value(json!({
+            "kind": "toolchains",
+            "version": { "major": 1, "minor": 0 },
+            "toolchains": [{
+                "language": "CXX",
+                "compiler": {
+                    "id": "GNU",
+                    "implicit": { "includeDirectories": [ "/usr/include/c++/13" ] }
+                }
+            }]
+        }))
+        .unwrap();
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/work/project".into();
+        codemodel.paths.build = "/work/project/build".into();
+        codemodel.configurations.push(Configuration {
+            targets: vec![target],
+            ..Default::default()
+        });
+
+        let config = clangd_config(&codemodel, Some(&toolchains));
+        assert_eq!(config.fragments.len(), 1);
+        assert_eq!(config.fragments[0].path_match, "src/[^/]*");
+        assert_eq!(
+            config.fragments[0].remove,
+            vec!["-fno-semantic-interposition", "/MP*"]
+        );
+
+        assert_eq!(
+            config.to_string(),
+            "CompileFlags:
+  CompilationDatabase: '/work/project/build'
+---
+If:
+  PathMatch: 'src/[^/]*'
+CompileFlags:
+  Add:
+    - '-isystem'
+    - '/usr/include/c++/13'
+  Remove:
+    - '-fno-semantic-interposition'
+    - '/MP*'
+"
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! Visual Studio Code `c_cpp_properties.json` and `launch.json` generation
+
+use crate::objects::codemodel_v2::{
+    CodeModel, CommandLineStyle, CompileSettings, Configuration, Target,
+};
+use crate::objects::toolchains_v1::{CompilerId, FrontendVariant, Toolchain, Toolchains};
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Content of a `c_cpp_properties.json` file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CppProperties {
+    pub configurations: Vec<CppConfiguration>,
+    pub version: u32,
+}
+
+/// A single configuration of `c_cpp_properties.json`
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CppConfiguration {
+    /// Name of the `CMake` configuration, e.g. `Debug`.
+    pub name: String,
+
+    /// Include and system include directories of all compile groups.
+    pub include_path: Vec<String>,
+
+    /// Preprocessor definitions of all compile groups as `<name>[=<value>]`.
+    pub defines: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compiler_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub c_standard: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpp_standard: Option<String>,
+
+    /// Mode like `linux-gcc-x64`, derived from the compiler id and target or the host architecture.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intelli_sense_mode: Option<String>,
+}
+
+/// Content of a `launch.json` file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Launch {
+    pub version: String,
+    pub configurations: Vec<LaunchConfiguration>,
+}
+
+/// A single debug configuration of `launch.json`
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LaunchConfiguration {
+    /// Target name, suffixed with the configuration if the codemodel has several configurations.
+    pub name: String,
+
+    /// Debugger type, `cppvsdbg` for MSVC toolchains and `cppdbg` otherwise.
+    #[serde(rename = "type")]
+    pub type_name: String,
+
+    pub request: String,
+
+    /// Absolute path of the executable or of its emulator when cross compiling.
+    pub program: String,
+
+    /// Emulator arguments followed by the executable.
+    pub args: Vec<String>,
+
+    /// Debugger working directory of the target or its build directory.
+    pub cwd: String,
+
+    /// `gdb` or `lldb` for `cppdbg`.
+    #[serde(rename = "MIMode", skip_serializing_if = "Option::is_none")]
+    pub mi_mode: Option<String>,
+}
+
+/// Generate `c_cpp_properties.json` with one configuration per codemodel configuration
+///
+/// Without toolchains no `compilerPath` and `intelliSenseMode` are set. Without a compiler target triple the
+/// architecture of the host is assumed.
+#[must_use]
+pub fn cpp_properties(codemodel: &CodeModel, toolchains: Option<&Toolchains>) -> CppProperties {
+    cpp_properties_on_host(codemodel, toolchains, env::consts::ARCH)
+}
+
+/// Generate `launch.json` with one entry per executable target and configuration
+///
+/// The emulator of a target is only launched when the compiler target triple names an architecture other than
+/// the one of the host, otherwise the executable is debugged directly. Test launchers are never used.
+#[must_use]
+pub fn launch(codemodel: &CodeModel, toolchains: Option<&Toolchains>) -> Launch {
+    launch_on_host(codemodel, toolchains, env::consts::ARCH)
+}
+
+fn cpp_properties_on_host(
+    codemodel: &CodeModel,
+    toolchains: Option<&Toolchains>,
+    host_arch: &str,
+) -> CppProperties {
+    let compiler = primary_toolchain(toolchains);
+
+    CppProperties {
+        configurations: codemodel
+            .configurations
+            .iter()
+            .map(|config| cpp_configuration(codemodel, config, compiler, host_arch))
+            .collect(),
+        version: 4,
+    }
+}
+
+fn launch_on_host(
+    codemodel: &CodeModel,
+    toolchains: Option<&Toolchains>,
+    host_arch: &str,
+) -> Launch {
+    let msvc = primary_toolchain(toolchains).map_or(false, |toolchain| {
+        toolchain.compiler.parsed_id() == Some(CompilerId::Msvc)
+            || toolchain.compiler.frontend_variant() == Some(FrontendVariant::Msvc)
+    });
+    let lldb = primary_toolchain(toolchains).map_or(false, |toolchain| {
+        toolchain.compiler.parsed_id() == Some(CompilerId::AppleClang)
+    });
+    // without a target triple the executables are assumed to run on the host
+    let emulate = primary_toolchain(toolchains)
+        .and_then(|toolchain| toolchain.compiler.parsed_target())
+        .map_or(false, |triple| {
+            normalized_arch(&triple.arch) != normalized_arch(host_arch)
+        });
+
+    let mut configurations = Vec::new();
+    for config in &codemodel.configurations {
+        for target in config
+            .targets
+            .iter()
+            .filter(|target| target.type_name == "EXECUTABLE")
+        {
+            let executable = match target.artifacts.first() {
+                Some(artifact) => &artifact.path,
+                None => continue,
+            };
+            let name = if codemodel.configurations.len() > 1 {
+                format!("{} ({})", target.name, config.name)
+            } else {
+                target.name.clone()
+            };
+            configurations.push(launch_configuration(
+                codemodel, target, name, executable, msvc, lldb, emulate,
+            ));
+        }
+    }
+
+    Launch {
+        version: "0.2.0".to_owned(),
+        configurations,
+    }
+}
+
+/// Write `c_cpp_properties.json` and `launch.json` into `dir`, usually the `.vscode` directory of the workspace
+///
+/// # Errors
+///
+/// `std::io::Error`: if the directory can not be created or a file can not be written
+pub fn write<P: AsRef<Path>>(
+    dir: P,
+    codemodel: &CodeModel,
+    toolchains: Option<&Toolchains>,
+) -> io::Result<()> {
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.as_ref().join("c_cpp_properties.json"),
+        serde_json::to_string_pretty(&cpp_properties(codemodel, toolchains))?,
+    )?;
+    fs::write(
+        dir.as_ref().join("launch.json"),
+        serde_json::to_string_pretty(&launch(codemodel, toolchains))?,
+    )?;
+    Ok(())
+}
+
+/// Toolchain used for the compiler path, CXX is preferred over C
+fn primary_toolchain(toolchains: Option<&Toolchains>) -> Option<&Toolchain> {
+    let available = toolchains?;
+    available
+        .toolchain("CXX")
+        .or_else(|| available.toolchain("C"))
+}
+
+fn cpp_configuration(
+    codemodel: &CodeModel,
+    config: &Configuration,
+    compiler: Option<&Toolchain>,
+    host_arch: &str,
+) -> CppConfiguration {
+    let mut properties = CppConfiguration {
+        name: config.name.clone(),
+        compiler_path: compiler
+            .and_then(|toolchain| toolchain.compiler.path.as_deref())
+            .map(paths::normalize),
+        intelli_sense_mode: compiler.and_then(|toolchain| intelli_sense_mode(toolchain, host_arch)),
+        ..Default::default()
+    };
+
+    let style = compiler.map_or(CommandLineStyle::Posix, |toolchain| {
+        CommandLineStyle::for_compiler(&toolchain.compiler)
+    });
+    // fragments that can not be split are not generated by `CMake`, their groups are skipped
+    for settings in config
+        .targets
+        .iter()
+        .flat_map(|target| &target.compile_groups)
+        .filter_map(|group| CompileSettings::from_group(group, style).ok())
+    {
+        for include in settings.includes.iter().chain(&settings.system_includes) {
+            let include_path = paths::resolve(&codemodel.paths.source, include);
+            if !properties.include_path.contains(&include_path) {
+                properties.include_path.push(include_path);
+            }
+        }
+        for define in &settings.defines {
+            if !properties.defines.contains(define) {
+                properties.defines.push(define.clone());
+            }
+        }
+
+        match settings.std() {
+            Some(std) if settings.is_cpp() => {
+                properties.cpp_standard = newest_standard(properties.cpp_standard.take(), std);
+            }
+            Some(std) if settings.language == "C" => {
+                let c_std = std.replace("90", "89");
+                properties.c_standard = newest_standard(properties.c_standard.take(), c_std);
+            }
+            _ => {}
+        }
+    }
+
+    properties
+}
+
+fn launch_configuration(
+    codemodel: &CodeModel,
+    target: &Target,
+    name: String,
+    executable: &Path,
+    msvc: bool,
+    lldb: bool,
+    emulate: bool,
+) -> LaunchConfiguration {
+    let executable_path = paths::resolve(&codemodel.paths.build, executable);
+
+    let emulator = target
+        .launchers
+        .iter()
+        .find(|launcher| emulate && launcher.launcher_type == "emulator");
+    let (program, args) = match emulator {
+        Some(launcher) => {
+            let mut launcher_args = launcher.arguments.clone();
+            launcher_args.push(executable_path);
+            (
+                paths::resolve(&codemodel.paths.source, Path::new(&launcher.command)),
+                launcher_args,
+            )
+        }
+        None => (executable_path, Vec::new()),
+    };
+
+    let cwd = target
+        .debugger
+        .as_ref()
+        .and_then(|debugger| debugger.working_directory.as_deref())
+        .map_or_else(
+            || paths::resolve(&codemodel.paths.build, &target.paths.build),
+            |dir| paths::resolve(&codemodel.paths.build, dir),
+        );
+
+    LaunchConfiguration {
+        name,
+        type_name: if msvc { "cppvsdbg" } else { "cppdbg" }.to_owned(),
+        request: "launch".to_owned(),
+        program,
+        args,
+        cwd,
+        mi_mode: (!msvc).then(|| if lldb { "lldb" } else { "gdb" }.to_owned()),
+    }
+}
+
+/// `<platform>-<compiler>-<arch>` mode of the `C/C++` extension
+fn intelli_sense_mode(toolchain: &Toolchain, host_arch: &str) -> Option<String> {
+    let compiler = &toolchain.compiler;
+    let id = compiler.parsed_id()?;
+    let target = compiler.parsed_target();
+
+    let msvc_frontend = compiler.frontend_variant() == Some(FrontendVariant::Msvc);
+    let compiler_name = if msvc_frontend {
+        "msvc"
+    } else if id.is_clang_based() {
+        "clang"
+    } else if id == CompilerId::Gnu {
+        "gcc"
+    } else {
+        return None;
+    };
+
+    let os = target.as_ref().and_then(|triple| triple.os.as_deref());
+    let platform = if msvc_frontend || matches!(os, Some("windows" | "win32" | "mingw32")) {
+        "windows"
+    } else if id == CompilerId::AppleClang || matches!(os, Some("darwin" | "macos" | "ios")) {
+        "macos"
+    } else {
+        "linux"
+    };
+
+    let arch = target
+        .as_ref()
+        .map_or(host_arch, |triple| triple.arch.as_str());
+    let mode_arch = match normalized_arch(arch) {
+        known @ ("x64" | "x86" | "arm64" | "arm") => known,
+        _ => return None,
+    };
+
+    Some(format!("{platform}-{compiler_name}-{mode_arch}"))
+}
+
+/// Architecture name of the `C/C++` extension for a triple or `std::env::consts::ARCH` architecture
+fn normalized_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" | "amd64" | "x64" => "x64",
+        "i386" | "i486" | "i586" | "i686" | "x86" => "x86",
+        "aarch64" | "arm64" => "arm64",
+        _ if arch.starts_with("arm") => "arm",
+        _ => arch,
+    }
+}
+
+/// Keep the newer of two standards like `c++17` and `gnu++20`
+fn newest_standard(current: Option<String>, candidate: String) -> Option<String> {
+    fn year(standard: &str) -> u32 {
+        let digits: String = standard
+            .chars()
+            .rev()
+            .take_while(char::is_ascii_digit)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        match digits.parse::<u32>() {
+            Ok(short) if short >= 80 => 1900 + short,
+            Ok(short) => 2000 + short,
+            Err(_) => 0,
+        }
+    }
+
+    match current {
+        Some(standard) if year(&standard) >= year(&candidate) => Some(standard),
+        _ => Some(candidate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ide::vscode::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_vscode() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "directoryIndex": 0,
+            "projectIndex": 0,
+            "paths": { "source": "app", "build": "app" },
+            "artifacts": [ { "path": "app/app" } ],
+            "launchers": [
+                { "command": "/usr/bin/qemu-aarch64", "arguments": ["-L", "/sysroot"], "type": "emulator" }
+            ],
+            "debugger": { "workingDirectory": "/data" },
+            "compileGroups": [
+                {
+                    "language": "CXX",
+                    "sourceIndexes": [0],
+                    "includes": [ { "path": "/src/include" } ],
+                    "defines": [ { "define": "APP=1" } ],
+                    "languageStandard": { "standard": "17" }
+                },
+                {
+                    "language": "C",
+                    "sourceIndexes": [1],
+                    "includes": [ { "path": "/src/include" } ],
+                    "languageStandard": { "standard": "90" }
+                }
+            ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/src".into();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![target],
+            ..Default::default()
+        });
+        let toolchains: Toolchains = serde_json::from_value(json!({
+            "kind": "toolchains",
+            "version": { "major": 1, "minor": 0 },
+            "toolchains": [{
+                "language": "CXX",
+                "compiler": {
+                    "id": "GNU",
+                    "path": "/usr/bin/aarch64-linux-gnu-g++",
+                    "target": "aarch64-linux-gnu",
+                    "implicit": {}
+                }
+            }]
+        }))
+        .unwrap();
+
+        let properties = cpp_properties_on_host(&codemodel, Some(&toolchains), "x86_64");
+        let config = &properties.configurations[0];
+        assert_eq!(config.name, "Debug");
+        assert_eq!(config.include_path, vec!["/src/include"]);
+        assert_eq!(config.defines, vec!["APP=1"]);
+        assert_eq!(config.cpp_standard.as_deref(), Some("c++17"));
+        assert_eq!(config.c_standard.as_deref(), Some("c89"));
+        assert_eq!(
+            config.compiler_path.as_deref(),
+            Some("/usr/bin/aarch64-linux-gnu-g++")
+        );
+        assert_eq!(
+            config.intelli_sense_mode.as_deref(),
+            Some("linux-gcc-arm64")
+        );
+
+        let cross = launch_on_host(&codemodel, Some(&toolchains), "x86_64");
+        assert_eq!(
+            serde_json::to_value(&cross.configurations[0]).unwrap(),
+            json!({
+                "name": "app",
+                "type": "cppdbg",
+                "request": "launch",
+                "program": "/usr/bin/qemu-aarch64",
+                "args": ["-L", "/sysroot", "/build/app/app"],
+                "cwd": "/data",
+                "MIMode": "gdb"
+            })
+        );
+
+        // on a native aarch64 host the executable is debugged directly
+        let native = launch_on_host(&codemodel, Some(&toolchains), "aarch64");
+        assert_eq!(native.configurations[0].program, "/build/app/app");
+        assert!(native.configurations[0].args.is_empty());
+
+        // without a target triple the host architecture is used and no emulation is assumed
+        let mut host_toolchains = toolchains.clone();
+        host_toolchains.toolchains[0].compiler.target = None;
+        let host_properties = cpp_properties_on_host(&codemodel, Some(&host_toolchains), "aarch64");
+        assert_eq!(
+            host_properties.configurations[0]
+                .intelli_sense_mode
+                .as_deref(),
+            Some("linux-gcc-arm64")
+        );
+        let host_launch = launch_on_host(&codemodel, Some(&host_toolchains), "x86_64");
+        assert_eq!(host_launch.configurations[0].program, "/build/app/app");
+    }
+}
@@ -1,12 +1,11 @@
 use crate::objects::{MajorMinor, ObjectKind};
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct Index {
     /// information about the instance of `CMake` that generated the reply
@@ -17,22 +16,30 @@ pub struct Index {
 
     /// map of replies to client queries
     pub reply: HashMap<String, ReplyField>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct CMake {
     pub version: CMakeVersion,
     pub paths: CMakePaths,
     pub generator: CMakeGenerator,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 /// information about the instance of `CMake` that generated the reply
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct CMakeVersion {
     /// specifying the major version component
@@ -52,12 +59,16 @@ pub struct CMakeVersion {
 
     /// indicating whether the version was built from a version controlled source tree with local modifications
     pub is_dirty: bool,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 /// paths to things that come with `CMake`
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct CMakePaths {
     /// absolute path to cmake tool
@@ -71,12 +82,16 @@ pub struct CMakePaths {
 
     /// absolute path to the directory containing CMake resources like the Modules/ directory
     pub root: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 /// describing the `CMake` generator used for the build
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct CMakeGenerator {
     /// specifying whether the generator supports multiple output configurations
@@ -87,12 +102,16 @@ pub struct CMakeGenerator {
 
     /// If the generator supports CMAKE_GENERATOR_PLATFORM, this is a string specifying the generator platform name
     pub platform: Option<String>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 /// represents a reference to another reply file
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct ReplyFileReference {
     /// specifying one of the Object Kinds
@@ -103,16 +122,25 @@ pub struct ReplyFileReference {
 
     /// path relative to the reply index file to another JSON file containing the object
     pub json_file: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct Error {
     pub error: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged)]
 #[non_exhaustive]
 pub enum ClientField {
@@ -121,6 +149,29 @@ pub enum ClientField {
     QueryJson(QueryJson),
 }
 
+impl<'de> Deserialize<'de> for ClientField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // select the variant by its distinguishing member instead of trying each variant in turn,
+        // so a malformed reply file reference is reported and not read as a `query.json` reply
+        let value = Value::deserialize(deserializer)?;
+        let field = if value.get("error").is_some() {
+            Error::deserialize(&value).map(ClientField::Error)
+        } else if value.get("jsonFile").is_some() {
+            ReplyFileReference::deserialize(&value).map(ClientField::ReplyFileReference)
+        } else if ["client", "requests", "responses"]
+            .iter()
+            .any(|member| value.get(member).is_some())
+        {
+            QueryJson::deserialize(&value).map(ClientField::QueryJson)
+        } else {
+            return Err(de::Error::custom(
+                "expected an error, a reply file reference or a query.json reply",
+            ));
+        };
+        field.map_err(de::Error::custom)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[non_exhaustive]
@@ -133,12 +184,16 @@ pub enum ReplyField {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(deny_unknown_fields)]
 #[non_exhaustive]
 pub struct QueryJson {
     pub client: Option<Value>,
     pub requests: Option<Value>,
     pub responses: Option<Value>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[cfg(test)]
@@ -185,18 +240,22 @@ mod testing {
                     patch: 7,
                     string: "3.27.7".into(),
                     suffix: String::new(),
+                    ..Default::default()
                 },
                 paths: CMakePaths {
                     cmake: "C:/Program Files/CMake/bin/cmake.exe".into(),
                     cpack: "C:/Program Files/CMake/bin/cpack.exe".into(),
                     ctest: "C:/Program Files/CMake/bin/ctest.exe".into(),
                     root: "C:/Program Files/CMake/share/cmake-3.27".into(),
+                    ..Default::default()
                 },
                 generator: CMakeGenerator {
                     multi_config: true,
                     platform: Some("x64".into()),
                     name: "Visual Studio 16 2019".into(),
+                    ..Default::default()
                 },
+                ..Default::default()
             }
         );
     }
@@ -228,12 +287,16 @@ mod testing {
           }
         });
 
+        // unknown fields are tolerated, e.g. fields of newer `CMake` versions
+        let cmake = serde_json::from_value::<CMake>(json.clone()).unwrap();
+        assert_eq!(cmake.generator.name, "Visual Studio 16 2019");
         assert_eq!(
-            serde_json::from_value::<CMake>(json)
-                .unwrap_err()
-                .to_string(),
-            "unknown field `test`, expected one of `multiConfig`, `name`, `platform`"
+            crate::extras::unknown_fields::<CMake>(&json).unwrap(),
+            vec!["/generator/test"]
         );
+
+        #[cfg(feature = "extras")]
+        assert_eq!(cmake.generator.extras["test"], "test");
     }
 
     #[test]
@@ -266,12 +329,22 @@ mod testing {
                 ReplyFileReference {
                     json_file: "codemodel-v2-b29a741ae0dbe513e631.json".into(),
                     kind: ObjectKind::CodeModel,
-                    version: MajorMinor { major: 2, minor: 6 }
+                    version: MajorMinor {
+                        major: 2,
+                        minor: 6,
+                        ..Default::default()
+                    },
+                    ..Default::default()
                 },
                 ReplyFileReference {
                     json_file: "configureLog-v1-cac906d276896c7cc320.json".into(),
                     kind: ObjectKind::ConfigureLog,
-                    version: MajorMinor { major: 1, minor: 0 }
+                    version: MajorMinor {
+                        major: 1,
+                        minor: 0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
                 }
             ]
         );
@@ -317,7 +390,12 @@ mod testing {
                 *e == ReplyFileReference {
                     json_file: "codemodel-v2-b29a741ae0dbe513e631.json".into(),
                     kind: ObjectKind::CodeModel,
-                    version: MajorMinor { major: 2, minor: 6 },
+                    version: MajorMinor {
+                        major: 2,
+                        minor: 6,
+                        ..Default::default()
+                    },
+                    ..Default::default()
                 },
             _ => false,
         });
@@ -346,7 +424,12 @@ mod testing {
                 *e == ReplyFileReference {
                     json_file: "codemodel-v2-b29a741ae0dbe513e631.json".into(),
                     kind: ObjectKind::CodeModel,
-                    version: MajorMinor { major: 2, minor: 6 },
+                    version: MajorMinor {
+                        major: 2,
+                        minor: 6,
+                        ..Default::default()
+                    },
+                    ..Default::default()
                 },
             _ => false,
         });
@@ -370,6 +453,22 @@ mod testing {
         });
     }
 
+    #[test]
+    fn test_reply_client_with_malformed_entry() {
+        // a reference without version is not mistaken for a query.json reply
+        let reference = json!({
+            "codemodel-v2" :
+            {
+                "jsonFile" : "codemodel-v2-b29a741ae0dbe513e631.json",
+                "kind" : "codemodel"
+            }
+        });
+        assert!(serde_json::from_value::<HashMap<String, ClientField>>(reference).is_err());
+
+        let query = json!({ "query.json" : { "unexpected" : true } });
+        assert!(serde_json::from_value::<HashMap<String, ClientField>>(query).is_err());
+    }
+
     #[test]
     fn test_reply_query_json_with_client() {
         let json = json!({
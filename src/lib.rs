@@ -64,7 +64,12 @@
 #![forbid(clippy::shadow_unrelated)]
 #![forbid(clippy::exhaustive_enums)]
 
+#[cfg(feature = "build-script")]
+pub mod build_script;
+pub mod extras;
+pub mod ide;
 pub mod index;
 pub mod objects;
+mod paths;
 pub mod query;
 pub mod reply;
@@ -4,6 +4,7 @@ pub mod cache_v2;
 pub mod cmake_files_v1;
 pub mod codemodel_v2;
 pub mod configure_log_v1;
+pub mod relocation;
 pub mod toolchains_v1;
 
 pub use cache_v2::Cache as CacheV2;
@@ -20,6 +21,11 @@ use crate::reply;
 pub struct MajorMinor {
     pub major: u32,
     pub minor: u32,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -15,6 +15,11 @@ pub struct Cache {
 
     /// Entries in the cache
     pub entries: Vec<Entry>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 /// Entry in the cache
@@ -34,6 +39,11 @@ pub struct Entry {
 
     /// Properties of the entry
     pub properties: Vec<Property>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -45,6 +55,11 @@ pub struct Property {
 
     /// Value of the property
     pub value: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 impl Object for Cache {
@@ -98,7 +113,11 @@ mod tests {
             cache,
             Cache {
                 kind: ObjectKind::Cache,
-                version: MajorMinor { major: 2, minor: 0 },
+                version: MajorMinor {
+                    major: 2,
+                    minor: 0,
+                    ..Default::default()
+                },
                 entries: vec![
                     Entry {
                         name: "BUILD_SHARED_LIBS".into(),
@@ -107,7 +126,9 @@ mod tests {
                         properties: vec![Property {
                             name: "HELPSTRING".into(),
                             value: "Build shared libraries".into(),
-                        }]
+                            ..Default::default()
+                        }],
+                        ..Default::default()
                     },
                     Entry {
                         name: "CMAKE_GENERATOR".into(),
@@ -116,9 +137,12 @@ mod tests {
                         properties: vec![Property {
                             name: "HELPSTRING".into(),
                             value: "Name of generator.".into(),
-                        }]
+                            ..Default::default()
+                        }],
+                        ..Default::default()
                     }
-                ]
+                ],
+                ..Default::default()
             }
         );
     }
@@ -19,6 +19,11 @@ pub struct CMakeFiles {
 
     /// Input file used by CMake when configuring and generating the build system.
     pub inputs: Vec<Input>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -30,6 +35,11 @@ pub struct Paths {
 
     /// Absolute path to the top-level build directory, represented with forward slashes.
     pub source: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -52,6 +62,11 @@ pub struct Input {
     /// True if the path specifies a file in the CMake installation.
     #[serde(default, rename = "isCMake")]
     pub is_cmake: bool,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 impl Object for CMakeFiles {
@@ -103,10 +118,15 @@ mod tests {
             cmake_files,
             CMakeFiles {
                 kind: ObjectKind::CMakeFiles,
-                version: MajorMinor { major: 1, minor: 0 },
+                version: MajorMinor {
+                    major: 1,
+                    minor: 0,
+                    ..Default::default()
+                },
                 paths: Paths {
                     build: "/path/to/top-level-build-dir".into(),
-                    source: "/path/to/top-level-source-dir".into()
+                    source: "/path/to/top-level-source-dir".into(),
+                    ..Default::default()
                 },
                 inputs: vec![
                     Input {
@@ -129,7 +149,8 @@ mod tests {
                         path: "/path/to/cmake/Modules/CMakeGenericSystem.cmake".into(),
                         ..Default::default()
                     }
-                ]
+                ],
+                ..Default::default()
             }
         );
     }
@@ -1,9 +1,39 @@
 pub mod backtrace_graph;
 pub mod codemodel;
+pub mod command_line;
+pub mod compare;
+pub mod compile_settings;
+pub mod cxx_modules;
+pub mod diff;
 pub mod directory;
+pub mod header_owner;
+pub mod impact;
+pub mod index;
+pub mod install_manifest;
+pub mod link_command;
+pub mod navigation;
+pub mod packaging;
+pub mod source_index;
+pub mod source_tree;
 pub mod target;
+pub mod validate;
 
 pub use backtrace_graph::*;
 pub use codemodel::*;
+pub use command_line::*;
+pub use compare::*;
+pub use compile_settings::*;
+pub use cxx_modules::*;
+pub use diff::*;
 pub use directory::*;
+pub use header_owner::*;
+pub use impact::*;
+pub use index::*;
+pub use install_manifest::*;
+pub use link_command::*;
+pub use navigation::*;
+pub use packaging::*;
+pub use source_index::*;
+pub use source_tree::*;
 pub use target::*;
+pub use validate::*;
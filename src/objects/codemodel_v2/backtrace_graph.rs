@@ -1,4 +1,6 @@
+use super::index::{BacktraceCommandIdx, BacktraceFileIdx, BacktraceIdx};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 
 /// The backtraceGraph member of a "codemodel" version 2 "directory" object, or "codemodel" version 2 "target" object.
@@ -20,6 +22,11 @@ pub struct BacktraceGraph {
     /// If the file is inside the top-level source directory then the path is specified relative to that directory.
     /// Otherwise, the path is absolute.
     pub files: Vec<PathBuf>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,7 +34,7 @@ pub struct BacktraceGraph {
 #[non_exhaustive]
 pub struct Node {
     /// An unsigned integer 0-based index into the backtrace files array.
-    pub file: usize,
+    pub file: BacktraceFileIdx,
 
     /// An optional member present when the node represents a line within the file.
     /// The value is an unsigned integer 1-based line number.
@@ -35,11 +42,90 @@ pub struct Node {
 
     /// An optional member present when the node represents a command invocation within the file.
     /// The value is an unsigned integer 0-based index into the backtrace commands array.
-    pub command: Option<usize>,
+    pub command: Option<BacktraceCommandIdx>,
 
     /// An optional member present when the node is not the bottom of the call stack.
     /// The value is an unsigned integer 0-based index of another entry in the backtrace nodes array.
-    pub parent: Option<usize>,
+    pub parent: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+/// A resolved backtrace node
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BacktraceFrame {
+    /// `CMake` language file, relative to the top-level source directory if inside.
+    pub file: PathBuf,
+
+    /// 1-based line number.
+    pub line: Option<usize>,
+
+    /// Name of the invoked command.
+    pub command: Option<String>,
+}
+
+impl fmt::Display for BacktraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        if let Some(command) = &self.command {
+            write!(f, " ({command})")?;
+        }
+        Ok(())
+    }
+}
+
+impl BacktraceGraph {
+    /// Node by index, `None` if the index is out of range
+    #[must_use]
+    pub fn node(&self, index: BacktraceIdx) -> Option<&Node> {
+        self.nodes.get(index.get())
+    }
+
+    /// File by index, `None` if the index is out of range
+    #[must_use]
+    pub fn file(&self, index: BacktraceFileIdx) -> Option<&PathBuf> {
+        self.files.get(index.get())
+    }
+
+    /// Command name by index, `None` if the index is out of range
+    #[must_use]
+    pub fn command(&self, index: BacktraceCommandIdx) -> Option<&str> {
+        self.commands.get(index.get()).map(String::as_str)
+    }
+
+    /// Resolve the backtrace of a node, starting with the node itself followed by its callers
+    ///
+    /// Invalid indices end the backtrace.
+    #[must_use]
+    pub fn frames(&self, node: BacktraceIdx) -> Vec<BacktraceFrame> {
+        let mut frames = Vec::new();
+        let mut current = Some(node);
+        // a well-formed graph is a tree, the length limit guards against cycles
+        while let Some(index) = current.filter(|_| frames.len() < self.nodes.len()) {
+            let entry = match self.node(index) {
+                Some(entry) => entry,
+                None => break,
+            };
+            frames.push(BacktraceFrame {
+                file: self.file(entry.file).cloned().unwrap_or_default(),
+                line: entry.line,
+                command: entry
+                    .command
+                    .and_then(|command| self.command(command))
+                    .map(str::to_owned),
+            });
+            current = entry.parent;
+        }
+        frames
+    }
 }
 
 #[cfg(test)]
@@ -90,23 +176,34 @@ mod tests {
                 files: vec![PathBuf::from("CMakeLists.txt")],
                 nodes: vec![
                     Node {
-                        file: 0,
+                        file: 0.into(),
                         ..Default::default()
                     },
                     Node {
-                        file: 0,
-                        command: Some(0),
+                        file: 0.into(),
+                        command: Some(0.into()),
                         line: Some(4),
-                        parent: Some(0)
+                        parent: Some(0.into()),
+                        ..Default::default()
                     },
                     Node {
-                        file: 0,
-                        command: Some(1),
+                        file: 0.into(),
+                        command: Some(1.into()),
                         line: Some(9),
-                        parent: Some(0)
+                        parent: Some(0.into()),
+                        ..Default::default()
                     }
-                ]
+                ],
+                ..Default::default()
             }
         );
+
+        let frames = graph.frames(2.into());
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[0].to_string(),
+            "CMakeLists.txt:9 (target_link_libraries)"
+        );
+        assert_eq!(frames[1].to_string(), "CMakeLists.txt");
     }
 }
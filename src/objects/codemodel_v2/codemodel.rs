@@ -1,10 +1,10 @@
 #![allow(clippy::module_name_repetitions)]
 
-use crate::objects::codemodel_v2::{Directory, Target};
+use crate::objects::codemodel_v2::{Directory, DirectoryIdx, ProjectIdx, Target, TargetIdx};
 use crate::objects::{MajorMinor, Object, ObjectKind};
+use crate::reply;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use crate::reply;
 
 /// The codemodel object kind describes the build system structure as modeled by `CMake`.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -24,6 +24,11 @@ pub struct CodeModel {
     /// On single-configuration generators there is one entry for the value of the CMAKE_BUILD_TYPE variable.
     /// For multi-configuration generators there is an entry for each configuration listed in the CMAKE_CONFIGURATION_TYPES variable.
     pub configurations: Vec<Configuration>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +40,11 @@ pub struct CodemodelPaths {
 
     /// Absolute path to the top-level build directory, represented with forward slashes.
     pub source: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -72,6 +82,11 @@ pub struct Configuration {
     /// The position in the vector corresponds to the index in the target_refs vector.
     #[serde(skip)]
     pub targets: Vec<Target>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -93,22 +108,22 @@ pub struct DirectoryReference {
     /// Optional member that is present when the directory is not top-level.
     /// The value is an unsigned integer 0-based index of another entry in the main directories array
     /// that corresponds to the parent directory that added this directory as a subdirectory.
-    pub parent_index: Option<usize>,
+    pub parent_index: Option<DirectoryIdx>,
 
     /// Optional member that is present when the directory has subdirectories.
     /// Each entry corresponding to child directory created by the add_subdirectory() or subdirs() command.
     /// Each entry is an unsigned integer 0-based index of another entry in the main directories array.
     #[serde(default)]
-    pub child_indexes: Vec<usize>,
+    pub child_indexes: Vec<DirectoryIdx>,
 
     /// An unsigned integer 0-based index into the main projects array indicating the build system project to which the directory belongs.
-    pub project_index: usize,
+    pub project_index: ProjectIdx,
 
     /// Optional member that is present when the directory itself has targets, excluding those belonging to subdirectories.
     /// Each entry corresponding to the targets.
     /// Each entry is an unsigned integer 0-based index into the main targets array.
     #[serde(default)]
-    pub target_indexes: Vec<usize>,
+    pub target_indexes: Vec<TargetIdx>,
 
     /// Optional member present when a minimum required version of CMake is known for the directory.
     /// This is the `<min>` version given to the most local call to the cmake_minimum_required(VERSION) command in the directory itself or
@@ -122,6 +137,11 @@ pub struct DirectoryReference {
 
     /// Path relative to the codemodel file to another JSON file containing a "codemodel" version 2 "directory" object.
     pub json_file: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -133,6 +153,11 @@ pub struct MinimumCmakeVersion {
     /// Each component is an unsigned integer and the suffix may be an arbitrary string.
     #[serde(rename = "string")]
     pub version: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -145,24 +170,29 @@ pub struct Project {
     /// Optional member that is present when the project is not top-level.
     /// The value is an unsigned integer 0-based index of another entry in the main projects array that corresponds to the parent project
     /// that added this project as a subproject.
-    pub parent_index: Option<usize>,
+    pub parent_index: Option<ProjectIdx>,
 
     /// Optional member that is present when the project has subprojects.
     /// Entries corresponding to the subprojects.
     /// Each entry is an unsigned integer 0-based index of another entry in the main projects array.
     #[serde(default)]
-    pub child_indexes: Vec<usize>,
+    pub child_indexes: Vec<ProjectIdx>,
 
     /// Entries corresponding to build system directories that are part of the project.
     /// The first entry corresponds to the top-level directory of the project.
     /// Each entry is an unsigned integer 0-based index into the main directories array.
-    pub directory_indexes: Vec<usize>,
+    pub directory_indexes: Vec<DirectoryIdx>,
 
     /// Optional member that is present when the project itself has targets, excluding those belonging to subprojects.
     /// Entries corresponding to the targets.
     /// Each entry is an unsigned integer 0-based index into the main targets array.
     #[serde(default)]
-    pub target_indexes: Vec<usize>,
+    pub target_indexes: Vec<TargetIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -178,14 +208,33 @@ pub struct TargetReference {
 
     /// An unsigned integer 0-based index into the main directories array indicating
     /// the build system directory in which the target is defined.
-    pub directory_index: usize,
+    pub directory_index: DirectoryIdx,
 
     /// An unsigned integer 0-based index into the main projects array indicating the
     /// build system project in which the target is defined.
-    pub project_index: usize,
+    pub project_index: ProjectIdx,
 
     /// Path relative to the codemodel file to another JSON file containing a "codemodel" version 2 "target" object.
     pub json_file: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+impl Configuration {
+    /// Target object by index, `None` if the index is out of range or the targets are not resolved
+    #[must_use]
+    pub fn target(&self, index: TargetIdx) -> Option<&Target> {
+        self.targets.get(index.get())
+    }
+
+    /// Directory object by index, `None` if the index is out of range or the directories are not resolved
+    #[must_use]
+    pub fn directory(&self, index: DirectoryIdx) -> Option<&Directory> {
+        self.directories.get(index.get())
+    }
 }
 
 impl Object for CodeModel {
@@ -198,20 +247,18 @@ impl Object for CodeModel {
     }
 
     fn resolve_references(&mut self, reader: &reply::Reader) -> Result<(), reply::ReaderError> {
-        let reply_dir = reply::dir(reader.build_dir());
-
         // resolve targets and directories references
         for config in &mut self.configurations {
             for target_ref in &config.target_refs {
                 config
                     .targets
-                    .push(reply::Reader::parse_reply(reply_dir.join(&target_ref.json_file))?);
+                    .push(reader.read_reply_file(&target_ref.json_file)?);
             }
 
             for directory_ref in &config.directory_refs {
-                config.directories.push(reply::Reader::parse_reply(
-                    reply_dir.join(&directory_ref.json_file),
-                )?);
+                config
+                    .directories
+                    .push(reader.read_reply_file(&directory_ref.json_file)?);
             }
         }
 
@@ -293,12 +340,20 @@ mod tests {
 
         let model = serde_json::from_value::<CodeModel>(json).unwrap();
         assert_eq!(model.kind, objects::ObjectKind::CodeModel);
-        assert_eq!(model.version, MajorMinor { major: 2, minor: 6 });
+        assert_eq!(
+            model.version,
+            MajorMinor {
+                major: 2,
+                minor: 6,
+                ..Default::default()
+            }
+        );
         assert_eq!(
             model.paths,
             CodemodelPaths {
                 source: "/path/to/top-level-source-dir".into(),
-                build: "/path/to/top-level-build-dir".into()
+                build: "/path/to/top-level-build-dir".into(),
+                ..Default::default()
             }
         );
         assert_eq!(model.configurations.len(), 1);
@@ -0,0 +1,161 @@
+#![allow(clippy::module_name_repetitions)]
+
+use crate::objects::toolchains_v1::{Compiler, FrontendVariant, Toolchains};
+
+/// Quoting rules used to split command fragments into single arguments.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CommandLineStyle {
+    /// POSIX shell rules: backslashes escape, single and double quotes group.
+    #[default]
+    Posix,
+
+    /// Windows rules (`CommandLineToArgvW`): backslashes are literal unless they precede a double quote.
+    Windows,
+}
+
+/// Errors for splitting command fragments
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum SplitError {
+    #[error("unbalanced quotes in command fragment: {0}")]
+    UnbalancedQuotes(String),
+}
+
+impl CommandLineStyle {
+    /// Quoting rules of the command lines generated for a compiler
+    ///
+    /// MSVC-style drivers and compilers located on a Windows drive use Windows rules.
+    #[must_use]
+    pub fn for_compiler(compiler: &Compiler) -> Self {
+        let windows_path = compiler.path.as_ref().map_or(false, |path| {
+            let raw = path.to_string_lossy();
+            let bytes = raw.as_bytes();
+            bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+        });
+
+        if windows_path || compiler.frontend_variant() == Some(FrontendVariant::Msvc) {
+            CommandLineStyle::Windows
+        } else {
+            CommandLineStyle::Posix
+        }
+    }
+
+    /// Quoting rules for the toolchain of a language, POSIX if the toolchain is unknown
+    #[must_use]
+    pub fn for_language(toolchains: Option<&Toolchains>, language: &str) -> Self {
+        toolchains
+            .and_then(|available| available.toolchain(language))
+            .map_or(CommandLineStyle::Posix, |toolchain| {
+                Self::for_compiler(&toolchain.compiler)
+            })
+    }
+
+    /// Split a command fragment into single arguments
+    ///
+    /// # Errors
+    ///
+    /// `SplitError::UnbalancedQuotes`: if a quote is not closed or a trailing escape is incomplete
+    pub fn split(self, fragment: &str) -> Result<Vec<String>, SplitError> {
+        let args = match self {
+            CommandLineStyle::Posix => shlex::split(fragment),
+            CommandLineStyle::Windows => split_windows(fragment),
+        };
+        args.ok_or_else(|| SplitError::UnbalancedQuotes(fragment.to_owned()))
+    }
+}
+
+/// Split according to the rules of `CommandLineToArgvW`, `None` if a quote is not closed
+fn split_windows(fragment: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut chars = fragment.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' if !in_quotes => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => {
+                let mut count = 1;
+                while chars.peek() == Some(&'\\') {
+                    chars.next();
+                    count += 1;
+                }
+                if chars.peek() == Some(&'"') {
+                    // 2n backslashes + quote: n backslashes and a delimiting quote
+                    // 2n+1 backslashes + quote: n backslashes and a literal quote
+                    current.extend(std::iter::repeat('\\').take(count / 2));
+                    if count % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.extend(std::iter::repeat('\\').take(count));
+                }
+                in_arg = true;
+            }
+            '"' => {
+                if in_quotes && chars.peek() == Some(&'"') {
+                    chars.next();
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                in_arg = true;
+            }
+            _ => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Some(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::command_line::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            CommandLineStyle::Windows
+                .split(r#"C:\lib\x.lib "C:\Program Files\y.lib" /LIBPATH:C:\sdk\ -DX=\"a\""#),
+            Ok(vec![
+                r"C:\lib\x.lib".to_owned(),
+                r"C:\Program Files\y.lib".to_owned(),
+                r"/LIBPATH:C:\sdk\".to_owned(),
+                r#"-DX="a""#.to_owned(),
+            ])
+        );
+        assert_eq!(
+            CommandLineStyle::Windows.split(r#""C:\dir\\" """#),
+            Ok(vec![r"C:\dir\".to_owned(), String::new()])
+        );
+        assert_eq!(
+            CommandLineStyle::Posix.split(r"-I'/opt/my dir' -DX=\'"),
+            Ok(vec!["-I/opt/my dir".to_owned(), "-DX='".to_owned()])
+        );
+        assert!(matches!(
+            CommandLineStyle::Windows.split(r#"-I"C:\open"#),
+            Err(SplitError::UnbalancedQuotes(_))
+        ));
+        assert!(matches!(
+            CommandLineStyle::Posix.split("-I'/open"),
+            Err(SplitError::UnbalancedQuotes(_))
+        ));
+    }
+}
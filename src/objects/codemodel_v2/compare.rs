@@ -0,0 +1,394 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::{CodeModel, Configuration};
+use super::target::{CompileGroup, Target};
+use serde::{Deserialize, Serialize};
+
+/// Difference between two lists, order of the items is preserved
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ListDiff {
+    /// Items only in the first list.
+    pub removed: Vec<String>,
+
+    /// Items only in the second list.
+    pub added: Vec<String>,
+}
+
+impl ListDiff {
+    #[must_use]
+    pub fn new(first: &[String], second: &[String]) -> Self {
+        ListDiff {
+            removed: first
+                .iter()
+                .filter(|item| !second.contains(item))
+                .cloned()
+                .collect(),
+            added: second
+                .iter()
+                .filter(|item| !first.contains(item))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
+/// Differences of compile groups with the same language
+///
+/// Compile groups are lined up by language and their position among the groups of that language.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CompileGroupDiff {
+    /// Language of the compile groups.
+    pub language: String,
+
+    /// Position among the compile groups of the language.
+    pub position: usize,
+
+    /// Compile flags.
+    pub flags: ListDiff,
+
+    /// Preprocessor definitions.
+    pub defines: ListDiff,
+
+    /// Include directories.
+    pub includes: ListDiff,
+
+    /// Language standards if they differ.
+    pub language_standard: Option<(Option<String>, Option<String>)>,
+}
+
+impl CompileGroupDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+            && self.defines.is_empty()
+            && self.includes.is_empty()
+            && self.language_standard.is_none()
+    }
+}
+
+/// Differences of a target present in both configurations
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TargetComparison {
+    /// Id of the target.
+    pub id: String,
+
+    /// Name of the target.
+    pub name: String,
+
+    /// Source paths.
+    pub sources: ListDiff,
+
+    /// Compile groups that differ. A compile group without counterpart is compared to an empty group.
+    pub compile_groups: Vec<CompileGroupDiff>,
+
+    /// Link or archive command fragments.
+    pub link_fragments: ListDiff,
+
+    /// Artifact paths.
+    pub artifacts: ListDiff,
+
+    /// Ids of the dependencies.
+    pub dependencies: ListDiff,
+}
+
+impl TargetComparison {
+    /// Compare the same target of two configurations
+    #[must_use]
+    pub fn new(first: &Target, second: &Target) -> Self {
+        let mut compile_groups = Vec::new();
+        let mut languages: Vec<&str> = Vec::new();
+        for group in first.compile_groups.iter().chain(&second.compile_groups) {
+            if !languages.contains(&group.language.as_str()) {
+                languages.push(&group.language);
+            }
+        }
+        for language in languages {
+            let first_groups = groups_of_language(first, language);
+            let second_groups = groups_of_language(second, language);
+            let empty = CompileGroup::default();
+            for position in 0..first_groups.len().max(second_groups.len()) {
+                let diff = compare_compile_groups(
+                    language,
+                    position,
+                    first_groups.get(position).copied().unwrap_or(&empty),
+                    second_groups.get(position).copied().unwrap_or(&empty),
+                );
+                if !diff.is_empty() {
+                    compile_groups.push(diff);
+                }
+            }
+        }
+
+        TargetComparison {
+            id: second.id.clone(),
+            name: second.name.clone(),
+            sources: ListDiff::new(&source_paths(first), &source_paths(second)),
+            compile_groups,
+            link_fragments: ListDiff::new(&link_fragments(first), &link_fragments(second)),
+            artifacts: ListDiff::new(&artifact_paths(first), &artifact_paths(second)),
+            dependencies: ListDiff::new(&dependency_ids(first), &dependency_ids(second)),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+            && self.compile_groups.is_empty()
+            && self.link_fragments.is_empty()
+            && self.artifacts.is_empty()
+            && self.dependencies.is_empty()
+    }
+}
+
+/// Comparison of two configurations of the same codemodel, e.g. Debug and Release
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ConfigurationComparison {
+    /// Name of the first configuration.
+    pub first: String,
+
+    /// Name of the second configuration.
+    pub second: String,
+
+    /// Ids of targets only in the first configuration.
+    pub only_in_first: Vec<String>,
+
+    /// Ids of targets only in the second configuration.
+    pub only_in_second: Vec<String>,
+
+    /// Targets present in both configurations that differ.
+    pub targets: Vec<TargetComparison>,
+}
+
+impl ConfigurationComparison {
+    /// Compare two configurations, targets are lined up by id
+    #[must_use]
+    pub fn new(first: &Configuration, second: &Configuration) -> Self {
+        let find = |config: &'_ Configuration, id: &str| -> bool {
+            config.targets.iter().any(|target| target.id == id)
+        };
+
+        ConfigurationComparison {
+            first: first.name.clone(),
+            second: second.name.clone(),
+            only_in_first: first
+                .targets
+                .iter()
+                .filter(|target| !find(second, &target.id))
+                .map(|target| target.id.clone())
+                .collect(),
+            only_in_second: second
+                .targets
+                .iter()
+                .filter(|target| !find(first, &target.id))
+                .map(|target| target.id.clone())
+                .collect(),
+            targets: first
+                .targets
+                .iter()
+                .filter_map(|first_target| {
+                    let second_target = second
+                        .targets
+                        .iter()
+                        .find(|target| target.id == first_target.id)?;
+                    let comparison = TargetComparison::new(first_target, second_target);
+                    (!comparison.is_empty()).then_some(comparison)
+                })
+                .collect(),
+        }
+    }
+
+    /// Comparison of a single target
+    #[must_use]
+    pub fn target(&self, name: &str) -> Option<&TargetComparison> {
+        self.targets.iter().find(|target| target.name == name)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.only_in_first.is_empty() && self.only_in_second.is_empty() && self.targets.is_empty()
+    }
+}
+
+impl CodeModel {
+    /// Compare two configurations by name
+    ///
+    /// Returns `None` if one of the configurations does not exist.
+    #[must_use]
+    pub fn compare_configurations(
+        &self,
+        first: &str,
+        second: &str,
+    ) -> Option<ConfigurationComparison> {
+        let find = |name: &str| {
+            self.configurations
+                .iter()
+                .find(|config| config.name == name)
+        };
+        Some(ConfigurationComparison::new(find(first)?, find(second)?))
+    }
+}
+
+fn groups_of_language<'a>(target: &'a Target, language: &str) -> Vec<&'a CompileGroup> {
+    target
+        .compile_groups
+        .iter()
+        .filter(|group| group.language == language)
+        .collect()
+}
+
+fn compare_compile_groups(
+    language: &str,
+    position: usize,
+    first: &CompileGroup,
+    second: &CompileGroup,
+) -> CompileGroupDiff {
+    let defines = |group: &CompileGroup| -> Vec<String> {
+        group
+            .defines
+            .iter()
+            .map(|define| define.define.clone())
+            .collect()
+    };
+    let includes = |group: &CompileGroup| -> Vec<String> {
+        group
+            .includes
+            .iter()
+            .map(|include| include.path.display().to_string())
+            .collect()
+    };
+    let standard = |group: &CompileGroup| -> Option<String> {
+        group
+            .language_standard
+            .as_ref()
+            .map(|standard| standard.standard.clone())
+    };
+
+    CompileGroupDiff {
+        language: language.to_owned(),
+        position,
+        flags: ListDiff::new(&first.compile_fragments(), &second.compile_fragments()),
+        defines: ListDiff::new(&defines(first), &defines(second)),
+        includes: ListDiff::new(&includes(first), &includes(second)),
+        language_standard: (standard(first) != standard(second))
+            .then(|| (standard(first), standard(second))),
+    }
+}
+
+fn source_paths(target: &Target) -> Vec<String> {
+    target
+        .sources
+        .iter()
+        .map(|source| source.path.display().to_string())
+        .collect()
+}
+
+fn link_fragments(target: &Target) -> Vec<String> {
+    let link = target.link.iter().flat_map(|link| &link.command_fragments);
+    let archive = target
+        .archive
+        .iter()
+        .flat_map(|archive| &archive.command_fragments);
+    link.chain(archive)
+        .map(|fragment| fragment.fragment.clone())
+        .collect()
+}
+
+fn artifact_paths(target: &Target) -> Vec<String> {
+    target
+        .artifacts
+        .iter()
+        .map(|artifact| artifact.path.display().to_string())
+        .collect()
+}
+
+fn dependency_ids(target: &Target) -> Vec<String> {
+    target
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::compare::*;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_configurations() {
+        let mut codemodel = CodeModel::default();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![target(json!({
+                "name": "app",
+                "artifacts": [ { "path": "Debug/app.exe" } ],
+                "sources": [ { "path": "main.cpp", "compileGroupIndex": 0 } ],
+                "compileGroups": [{
+                    "language": "CXX",
+                    "sourceIndexes": [0],
+                    "compileCommandFragments": [ { "fragment": "/Od /RTC1" } ],
+                    "defines": [ { "define": "_DEBUG" } ]
+                }],
+                "link": {
+                    "language": "CXX",
+                    "commandFragments": [ { "fragment": "kernel32.lib", "role": "libraries" } ]
+                }
+            }))],
+            ..Default::default()
+        });
+        codemodel.configurations.push(Configuration {
+            name: "Release".into(),
+            targets: vec![target(json!({
+                "name": "app",
+                "artifacts": [ { "path": "Release/app.exe" } ],
+                "sources": [ { "path": "main.cpp", "compileGroupIndex": 0 } ],
+                "compileGroups": [{
+                    "language": "CXX",
+                    "sourceIndexes": [0],
+                    "compileCommandFragments": [ { "fragment": "/O2" } ],
+                    "defines": [ { "define": "NDEBUG" } ]
+                }],
+                "link": {
+                    "language": "CXX",
+                    "commandFragments": [ { "fragment": "kernel32.lib", "role": "libraries" } ]
+                }
+            }))],
+            ..Default::default()
+        });
+
+        assert!(codemodel
+            .compare_configurations("Debug", "Missing")
+            .is_none());
+        assert!(codemodel
+            .compare_configurations("Debug", "Debug")
+            .unwrap()
+            .is_empty());
+
+        let comparison = codemodel
+            .compare_configurations("Debug", "Release")
+            .unwrap();
+        assert!(comparison.only_in_first.is_empty());
+        let app = comparison.target("app").unwrap();
+        assert!(app.sources.is_empty());
+        assert!(app.link_fragments.is_empty());
+        assert_eq!(app.artifacts.added, vec!["Release/app.exe"]);
+        assert_eq!(app.compile_groups.len(), 1);
+        assert_eq!(app.compile_groups[0].flags.removed, vec!["/Od", "/RTC1"]);
+        assert_eq!(app.compile_groups[0].flags.added, vec!["/O2"]);
+        assert_eq!(app.compile_groups[0].defines.added, vec!["NDEBUG"]);
+    }
+}
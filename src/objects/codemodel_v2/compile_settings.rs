@@ -0,0 +1,420 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::command_line::{CommandLineStyle, SplitError};
+use super::target::{CompileGroup, Target};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Compiler independent settings of a compile group, e.g. for bindgen or the cc crate.
+///
+/// MSVC-style fragments (`/D`, `/I`, `/external:I`, `/std:`) are translated to their clang spelling.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CompileSettings {
+    /// Language of the compile group (e.g. C, CXX).
+    pub language: String,
+
+    /// Language standard without language prefix, e.g. `17` or `gnu++17` if set as flag.
+    pub language_standard: Option<String>,
+
+    /// Include directories.
+    pub includes: Vec<PathBuf>,
+
+    /// System include directories.
+    pub system_includes: Vec<PathBuf>,
+
+    /// Framework directories (Apple).
+    pub frameworks: Vec<PathBuf>,
+
+    /// System framework directories (Apple).
+    pub system_frameworks: Vec<PathBuf>,
+
+    /// Preprocessor definitions as `<name>[=<value>]`.
+    pub defines: Vec<String>,
+
+    /// Path to the sysroot.
+    pub sysroot: Option<PathBuf>,
+
+    /// Remaining GNU-style (`-`) compile flags. MSVC-style flags have no clang equivalent and are dropped.
+    pub flags: Vec<String>,
+}
+
+/// A single method call on a `cc::Build`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum CcBuildCall {
+    /// `cc::Build::cpp`
+    Cpp(bool),
+
+    /// `cc::Build::std`
+    Std(String),
+
+    /// `cc::Build::include`
+    Include(PathBuf),
+
+    /// `cc::Build::define`
+    Define(String, Option<String>),
+
+    /// `cc::Build::flag`
+    Flag(String),
+}
+
+impl fmt::Display for CcBuildCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CcBuildCall::Cpp(cpp) => write!(f, ".cpp({cpp})"),
+            CcBuildCall::Std(std) => write!(f, ".std({std:?})"),
+            CcBuildCall::Include(path) => write!(f, ".include({:?})", path.display().to_string()),
+            CcBuildCall::Define(name, Some(value)) => {
+                write!(f, ".define({name:?}, Some({value:?}))")
+            }
+            CcBuildCall::Define(name, None) => write!(f, ".define({name:?}, None)"),
+            CcBuildCall::Flag(flag) => write!(f, ".flag({flag:?})"),
+        }
+    }
+}
+
+impl CompileSettings {
+    /// True for C++ like languages
+    #[must_use]
+    pub fn is_cpp(&self) -> bool {
+        matches!(self.language.as_str(), "CXX" | "OBJCXX" | "CUDA" | "HIP")
+    }
+
+    /// Language standard in clang spelling, e.g. `c++17` or `c11`
+    #[must_use]
+    pub fn std(&self) -> Option<String> {
+        let standard = self.language_standard.as_ref()?;
+        if standard.starts_with(|c: char| c.is_ascii_digit()) {
+            let prefix = if self.is_cpp() { "c++" } else { "c" };
+            Some(format!("{prefix}{standard}"))
+        } else {
+            Some(standard.clone())
+        }
+    }
+
+    /// Arguments for clang, e.g. `bindgen::Builder::clang_args`
+    #[must_use]
+    pub fn to_clang_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.is_cpp() {
+            args.push("-xc++".to_owned());
+        }
+        if let Some(std) = self.std() {
+            args.push(format!("-std={std}"));
+        }
+        if let Some(sysroot) = &self.sysroot {
+            args.push(format!("--sysroot={}", sysroot.display()));
+        }
+        args.extend(self.defines.iter().map(|define| format!("-D{define}")));
+        args.extend(
+            self.includes
+                .iter()
+                .map(|path| format!("-I{}", path.display())),
+        );
+        for path in &self.system_includes {
+            args.push("-isystem".to_owned());
+            args.push(path.display().to_string());
+        }
+        args.extend(
+            self.frameworks
+                .iter()
+                .map(|path| format!("-F{}", path.display())),
+        );
+        for path in &self.system_frameworks {
+            args.push("-iframework".to_owned());
+            args.push(path.display().to_string());
+        }
+        args.extend(self.flags.iter().cloned());
+        args
+    }
+
+    /// Method calls to configure a `cc::Build`
+    ///
+    /// `cc` has no notion of system include directories, so they are added as regular includes.
+    #[must_use]
+    pub fn to_cc_build_calls(&self) -> Vec<CcBuildCall> {
+        let mut calls = vec![CcBuildCall::Cpp(self.is_cpp())];
+        calls.extend(self.std().map(CcBuildCall::Std));
+        if let Some(sysroot) = &self.sysroot {
+            calls.push(CcBuildCall::Flag(format!(
+                "--sysroot={}",
+                sysroot.display()
+            )));
+        }
+        calls.extend(
+            self.defines
+                .iter()
+                .map(|define| match define.split_once('=') {
+                    Some((name, value)) => {
+                        CcBuildCall::Define(name.to_owned(), Some(value.to_owned()))
+                    }
+                    None => CcBuildCall::Define(define.clone(), None),
+                }),
+        );
+        calls.extend(
+            self.includes
+                .iter()
+                .chain(&self.system_includes)
+                .cloned()
+                .map(CcBuildCall::Include),
+        );
+        calls.extend(
+            self.frameworks
+                .iter()
+                .map(|path| CcBuildCall::Flag(format!("-F{}", path.display()))),
+        );
+        calls.extend(
+            self.system_frameworks
+                .iter()
+                .map(|path| CcBuildCall::Flag(format!("-iframework{}", path.display()))),
+        );
+        calls.extend(self.flags.iter().cloned().map(CcBuildCall::Flag));
+        calls
+    }
+
+    /// Compile settings of a compile group, fragments are split according to `style`
+    ///
+    /// # Errors
+    ///
+    /// `SplitError::UnbalancedQuotes`: if a compile fragment can not be split into arguments
+    pub fn from_group(group: &CompileGroup, style: CommandLineStyle) -> Result<Self, SplitError> {
+        let mut settings = CompileSettings {
+            language: group.language.clone(),
+            language_standard: group
+                .language_standard
+                .as_ref()
+                .map(|standard| standard.standard.clone()),
+            defines: group
+                .defines
+                .iter()
+                .map(|define| define.define.clone())
+                .collect(),
+            sysroot: group.sysroot.as_ref().map(|sysroot| sysroot.path.clone()),
+            ..Default::default()
+        };
+
+        for include in &group.includes {
+            if include.is_system {
+                settings.system_includes.push(include.path.clone());
+            } else {
+                settings.includes.push(include.path.clone());
+            }
+        }
+        for framework in &group.frameworks {
+            if framework.is_system {
+                settings.system_frameworks.push(framework.path.clone());
+            } else {
+                settings.frameworks.push(framework.path.clone());
+            }
+        }
+
+        let mut fragments = Vec::new();
+        for fragment in &group.compile_command_fragments {
+            fragments.extend(style.split(&fragment.fragment)?);
+        }
+        settings.push_fragment_args(fragments);
+        Ok(settings)
+    }
+
+    fn push_fragment_args(&mut self, fragments: Vec<String>) {
+        let mut iter = fragments.into_iter();
+        while let Some(arg) = iter.next() {
+            let mut separated = |prefix: &str| -> Option<String> {
+                if arg == prefix {
+                    iter.next()
+                } else {
+                    arg.strip_prefix(prefix).map(str::to_owned)
+                }
+            };
+
+            if let Some(define) = separated("-D").or_else(|| separated("/D")) {
+                self.defines.push(define);
+            } else if let Some(path) = separated("-isystem")
+                .or_else(|| separated("/external:I"))
+                .or_else(|| separated("-external:I"))
+            {
+                self.system_includes.push(path.into());
+            } else if let Some(path) = separated("-I").or_else(|| separated("/I")) {
+                self.includes.push(path.into());
+            } else if let Some(path) = separated("-iframework") {
+                self.system_frameworks.push(path.into());
+            } else if let Some(path) = separated("-F") {
+                self.frameworks.push(path.into());
+            } else if let Some(path) = arg.strip_prefix("--sysroot=") {
+                self.sysroot = Some(path.into());
+            } else if let Some(std) = arg.strip_prefix("-std=") {
+                self.language_standard = Some(std.to_owned());
+            } else if let Some(std) = arg
+                .strip_prefix("/std:")
+                .or_else(|| arg.strip_prefix("-std:"))
+            {
+                self.language_standard = Some(Self::msvc_standard(std).to_owned());
+            } else if arg.starts_with('-') {
+                self.flags.push(arg);
+            }
+        }
+    }
+
+    /// clang spelling of an MSVC `/std:` value, e.g. `c++latest` -> `c++2c`
+    fn msvc_standard(std: &str) -> &str {
+        match std {
+            "c++latest" => "c++2c",
+            "c++23preview" => "c++23",
+            "clatest" => "c2x",
+            other => other,
+        }
+    }
+}
+
+impl Target {
+    /// Compile settings of the first compile group with the given language, e.g. CXX
+    ///
+    /// Returns `None` if no compile group has the language.
+    ///
+    /// # Errors
+    ///
+    /// see [`CompileSettings::from_group`]
+    pub fn compile_settings(
+        &self,
+        language: &str,
+        style: CommandLineStyle,
+    ) -> Result<Option<CompileSettings>, SplitError> {
+        self.compile_groups
+            .iter()
+            .find(|group| group.language == language)
+            .map(|group| CompileSettings::from_group(group, style))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::compile_settings::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compile_settings() {
+        let json = json!({
+            "compileCommandFragments" :
+            [
+                {
+                    "fragment" : "/DWIN32 /D_WINDOWS /W3 /GR /EHsc"
+                },
+                {
+                    "fragment" : "/I C:/deps/include /external:I C:/sdk/include -fms-extensions"
+                }
+            ],
+            "defines" :
+            [
+                {
+                    "define" : "FOO=1"
+                }
+            ],
+            "includes" :
+            [
+                {
+                    "path" : "C:/project/include"
+                },
+                {
+                    "isSystem" : true,
+                    "path" : "C:/vcpkg/installed/include"
+                }
+            ],
+            "language" : "CXX",
+            "languageStandard" :
+            {
+                "backtraces" : [],
+                "standard" : "17"
+            },
+            "sourceIndexes" : [ 0 ]
+        });
+
+        let group = serde_json::from_value::<CompileGroup>(json).unwrap();
+        let settings = CompileSettings::from_group(&group, CommandLineStyle::Posix).unwrap();
+        assert_eq!(settings.defines, vec!["FOO=1", "WIN32", "_WINDOWS"]);
+        assert_eq!(
+            settings.includes,
+            vec![
+                PathBuf::from("C:/project/include"),
+                PathBuf::from("C:/deps/include")
+            ]
+        );
+        assert_eq!(
+            settings.system_includes,
+            vec![
+                PathBuf::from("C:/vcpkg/installed/include"),
+                PathBuf::from("C:/sdk/include")
+            ]
+        );
+        assert_eq!(settings.flags, vec!["-fms-extensions"]);
+
+        assert_eq!(
+            settings.to_clang_args(),
+            vec![
+                "-xc++",
+                "-std=c++17",
+                "-DFOO=1",
+                "-DWIN32",
+                "-D_WINDOWS",
+                "-IC:/project/include",
+                "-IC:/deps/include",
+                "-isystem",
+                "C:/vcpkg/installed/include",
+                "-isystem",
+                "C:/sdk/include",
+                "-fms-extensions",
+            ]
+        );
+
+        let calls = settings.to_cc_build_calls();
+        assert_eq!(calls[0], CcBuildCall::Cpp(true));
+        assert_eq!(calls[1], CcBuildCall::Std("c++17".into()));
+        assert_eq!(
+            calls[2],
+            CcBuildCall::Define("FOO".into(), Some("1".into()))
+        );
+        assert_eq!(calls[2].to_string(), r#".define("FOO", Some("1"))"#);
+        assert_eq!(
+            calls.last(),
+            Some(&CcBuildCall::Flag("-fms-extensions".into()))
+        );
+    }
+
+    #[test]
+    fn test_msvc_compile_settings() {
+        let json = json!({
+            "compileCommandFragments" :
+            [
+                {
+                    "fragment" : r#"/DWIN32 /I C:\src\inc "/IC:\Program Files\sdk\include" /std:c++latest"#
+                },
+                {
+                    "fragment" : r#"/external:I C:\deps\include /DNAME=\"app\""#
+                }
+            ],
+            "language" : "CXX",
+            "sourceIndexes" : [ 0 ]
+        });
+
+        let group = serde_json::from_value::<CompileGroup>(json).unwrap();
+        let settings = CompileSettings::from_group(&group, CommandLineStyle::Windows).unwrap();
+        assert_eq!(settings.defines, vec!["WIN32", r#"NAME="app""#]);
+        assert_eq!(
+            settings.includes,
+            vec![
+                PathBuf::from(r"C:\src\inc"),
+                PathBuf::from(r"C:\Program Files\sdk\include")
+            ]
+        );
+        assert_eq!(
+            settings.system_includes,
+            vec![PathBuf::from(r"C:\deps\include")]
+        );
+        assert_eq!(settings.std(), Some("c++2c".to_owned()));
+        assert!(settings.to_clang_args().contains(&"-std=c++2c".to_owned()));
+    }
+}
@@ -0,0 +1,359 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::Configuration;
+use super::directory::{Installer, TargetIdAndIndex};
+use super::index::{CompileGroupIdx, FileSetIdx, SourceIdx};
+use super::target::{FileSet, Target};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A `CXX_MODULES` or `CXX_MODULE_HEADER_UNITS` file set of a target with its sources
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ModuleFileSet {
+    /// Name of the file set.
+    pub name: String,
+
+    /// `CXX_MODULES` or `CXX_MODULE_HEADER_UNITS`.
+    pub type_name: String,
+
+    /// PUBLIC, PRIVATE or INTERFACE.
+    pub visibility: String,
+
+    /// Base directories of the file set.
+    pub base_directories: Vec<String>,
+
+    /// 0-based index into the file sets of the target.
+    pub file_set_index: FileSetIdx,
+
+    /// Sources of the file set.
+    pub sources: Vec<ModuleSource>,
+}
+
+/// A module interface, implementation unit or header unit
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ModuleSource {
+    /// Path of the source, as given in the target.
+    pub path: PathBuf,
+
+    /// 0-based index into the sources of the target.
+    pub source_index: SourceIdx,
+
+    /// 0-based index into the compile groups of the target, if the source is compiled.
+    pub compile_group_index: Option<CompileGroupIdx>,
+}
+
+/// Installation of C++ module BMIs or module sources
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ModuleInstall {
+    /// Installation destination, absolute or relative to the install prefix.
+    pub destination: Option<String>,
+
+    /// Install component.
+    pub component: String,
+
+    /// True when install() is called with the OPTIONAL option.
+    pub optional: bool,
+}
+
+/// Everything module-aware tools need to know about the C++ modules of a target
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ModuleManifest {
+    /// Name of the target.
+    pub target: String,
+
+    /// Id of the target.
+    pub id: String,
+
+    /// C++ standard of the module sources, e.g. `20`.
+    pub language_standard: Option<String>,
+
+    /// `CXX_MODULES` file sets.
+    pub modules: Vec<ModuleFileSet>,
+
+    /// `CXX_MODULE_HEADER_UNITS` file sets.
+    pub header_units: Vec<ModuleFileSet>,
+
+    /// Installations of the module BMIs (install(TARGETS ... CXX_MODULES_BMI)).
+    pub bmi_installs: Vec<ModuleInstall>,
+
+    /// Installations of the module sources (install(TARGETS ... FILE_SET)).
+    pub source_installs: Vec<ModuleInstall>,
+
+    /// Names of dependencies which provide modules themselves.
+    pub module_dependencies: Vec<String>,
+}
+
+impl FileSet {
+    /// True for `CXX_MODULES` file sets
+    #[must_use]
+    pub fn is_cxx_modules(&self) -> bool {
+        self.type_name == "CXX_MODULES"
+    }
+
+    /// True for `CXX_MODULE_HEADER_UNITS` file sets
+    #[must_use]
+    pub fn is_cxx_module_header_units(&self) -> bool {
+        self.type_name == "CXX_MODULE_HEADER_UNITS"
+    }
+}
+
+impl Target {
+    /// `CXX_MODULES` and `CXX_MODULE_HEADER_UNITS` file sets with their sources
+    ///
+    /// Sources are assigned to file sets by [`Source::file_set_index`](super::Source::file_set_index).
+    #[must_use]
+    pub fn module_file_sets(&self) -> Vec<ModuleFileSet> {
+        self.file_sets
+            .iter()
+            .enumerate()
+            .filter(|(_, file_set)| {
+                file_set.is_cxx_modules() || file_set.is_cxx_module_header_units()
+            })
+            .map(|(index, file_set)| (FileSetIdx::new(index), file_set))
+            .map(|(file_set_index, file_set)| ModuleFileSet {
+                name: file_set.name.clone(),
+                type_name: file_set.type_name.clone(),
+                visibility: file_set.visibility.clone(),
+                base_directories: file_set.base_directories.clone(),
+                file_set_index,
+                sources: self
+                    .sources
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, source)| source.file_set_index == Some(file_set_index))
+                    .map(|(source_index, source)| ModuleSource {
+                        path: source.path.clone(),
+                        source_index: SourceIdx::new(source_index),
+                        compile_group_index: source.compile_group_index,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// True if the target has `CXX_MODULES` or `CXX_MODULE_HEADER_UNITS` file sets
+    #[must_use]
+    pub fn has_modules(&self) -> bool {
+        self.file_sets
+            .iter()
+            .any(|file_set| file_set.is_cxx_modules() || file_set.is_cxx_module_header_units())
+    }
+}
+
+impl Configuration {
+    /// Installers of the module BMIs of a target
+    pub fn module_bmi_installers<'a>(
+        &'a self,
+        target_id: &'a str,
+    ) -> impl Iterator<Item = &'a Installer> + 'a {
+        self.installers().filter(move |installer| {
+            installer.installer_type == "cxxModuleBmi"
+                && refers_to(installer.cxx_module_bmi_target.as_ref(), target_id)
+        })
+    }
+
+    /// Module manifest of a target, `None` if the target does not exist or has no modules
+    #[must_use]
+    pub fn module_manifest(&self, target_name: &str) -> Option<ModuleManifest> {
+        let target = self
+            .targets
+            .iter()
+            .find(|target| target.name == target_name)?;
+        if !target.has_modules() {
+            return None;
+        }
+
+        let module_file_sets = target.module_file_sets();
+        let language_standard = module_file_sets
+            .iter()
+            .flat_map(|file_set| &file_set.sources)
+            .find_map(|source| source.compile_group_index)
+            .and_then(|index| target.compile_group(index))
+            .and_then(|group| group.language_standard.as_ref())
+            .map(|standard| standard.standard.clone());
+        let (modules, header_units) = module_file_sets
+            .into_iter()
+            .partition(|file_set| file_set.type_name == "CXX_MODULES");
+
+        let module_set_names: Vec<&str> = target
+            .file_sets
+            .iter()
+            .filter(|file_set| file_set.is_cxx_modules() || file_set.is_cxx_module_header_units())
+            .map(|file_set| file_set.name.as_str())
+            .collect();
+
+        Some(ModuleManifest {
+            target: target.name.clone(),
+            id: target.id.clone(),
+            language_standard,
+            modules,
+            header_units,
+            bmi_installs: self
+                .module_bmi_installers(&target.id)
+                .map(ModuleInstall::from)
+                .collect(),
+            source_installs: self
+                .installers()
+                .filter(|installer| {
+                    installer.installer_type == "fileSet"
+                        && refers_to(installer.file_set_target.as_ref(), &target.id)
+                        && installer
+                            .file_set_name
+                            .as_deref()
+                            .map_or(false, |name| module_set_names.contains(&name))
+                })
+                .map(ModuleInstall::from)
+                .collect(),
+            module_dependencies: target
+                .dependencies
+                .iter()
+                .filter_map(|dependency| {
+                    self.targets
+                        .iter()
+                        .find(|candidate| candidate.id == dependency.id)
+                })
+                .filter(|dependency| dependency.has_modules())
+                .map(|dependency| dependency.name.clone())
+                .collect(),
+        })
+    }
+
+    /// Module manifests of all targets with modules
+    #[must_use]
+    pub fn module_manifests(&self) -> Vec<ModuleManifest> {
+        self.targets
+            .iter()
+            .filter_map(|target| self.module_manifest(&target.name))
+            .collect()
+    }
+
+    fn installers(&self) -> impl Iterator<Item = &Installer> {
+        self.directories
+            .iter()
+            .flat_map(|directory| &directory.installers)
+    }
+}
+
+impl From<&Installer> for ModuleInstall {
+    fn from(installer: &Installer) -> Self {
+        ModuleInstall {
+            destination: installer.destination.clone(),
+            component: installer.component.clone(),
+            optional: installer.is_optional,
+        }
+    }
+}
+
+fn refers_to(target: Option<&TargetIdAndIndex>, target_id: &str) -> bool {
+    target.map_or(false, |reference| reference.id == target_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::cxx_modules::*;
+    use crate::objects::codemodel_v2::Directory;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_module_manifest() {
+        let module_sets = json!([
+            { "name": "CXX_MODULES", "type": "CXX_MODULES", "visibility": "PUBLIC", "baseDirectories": ["."] },
+            { "name": "units", "type": "CXX_MODULE_HEADER_UNITS", "visibility": "PRIVATE", "baseDirectories": ["."] }
+        ]);
+
+        let directory: Directory = serde_json::from_value(json!({
+            "paths": { "source": ".", "build": "." },
+            "installers": [
+                {
+                    "component": "Development",
+                    "destination": "lib/bmi",
+                    "type": "cxxModuleBmi",
+                    "cxxModuleBmiTarget": { "id": "math::@1", "index": 0 }
+                },
+                {
+                    "component": "Development",
+                    "destination": "include/math",
+                    "paths": [ "math.cppm" ],
+                    "type": "fileSet",
+                    "fileSetName": "CXX_MODULES",
+                    "fileSetType": "CXX_MODULES",
+                    "fileSetDirectories": [ "." ],
+                    "fileSetTarget": { "id": "math::@1", "index": 0 }
+                }
+            ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+
+        let config = Configuration {
+            targets: vec![
+                target(json!({
+                    "name": "math",
+                    "type": "STATIC_LIBRARY",
+                    "fileSets": module_sets.clone(),
+                    "sources": [
+                        { "path": "math.cppm", "compileGroupIndex": 0, "fileSetIndex": 0 },
+                        { "path": "impl.cpp", "compileGroupIndex": 0 },
+                        { "path": "legacy.h", "fileSetIndex": 1 }
+                    ],
+                    "compileGroups": [{
+                        "language": "CXX",
+                        "sourceIndexes": [0, 1],
+                        "languageStandard": { "standard": "20" }
+                    }]
+                })),
+                target(json!({
+                    "name": "app",
+                    "sources": [ { "path": "main.cpp", "compileGroupIndex": 0 } ],
+                    "dependencies": [ { "id": "math::@1" } ]
+                })),
+                target(json!({
+                    "name": "geometry",
+                    "type": "STATIC_LIBRARY",
+                    "fileSets": module_sets,
+                    "sources": [ { "path": "geometry.cppm", "compileGroupIndex": 0, "fileSetIndex": 0 } ],
+                    "dependencies": [ { "id": "math::@1" } ]
+                })),
+            ],
+            directories: vec![directory],
+            ..Default::default()
+        };
+
+        assert!(config.module_manifest("app").is_none());
+
+        let manifest = config.module_manifest("math").unwrap();
+        assert_eq!(manifest.language_standard.as_deref(), Some("20"));
+        assert_eq!(manifest.modules.len(), 1);
+        assert_eq!(
+            manifest.modules[0].sources[0].path,
+            PathBuf::from("math.cppm")
+        );
+        assert_eq!(
+            manifest.header_units[0].sources[0].source_index,
+            SourceIdx::new(2)
+        );
+        assert_eq!(
+            manifest.bmi_installs[0].destination.as_deref(),
+            Some("lib/bmi")
+        );
+        assert_eq!(
+            manifest.source_installs[0].destination.as_deref(),
+            Some("include/math")
+        );
+
+        let geometry = config.module_manifest("geometry").unwrap();
+        assert_eq!(geometry.module_dependencies, vec!["math"]);
+        assert!(geometry.bmi_installs.is_empty());
+
+        assert_eq!(config.module_manifests().len(), 2);
+    }
+}
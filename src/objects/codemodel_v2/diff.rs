@@ -0,0 +1,561 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::backtrace_graph::{BacktraceFrame, BacktraceGraph};
+use super::codemodel::{CodeModel, Configuration};
+use super::compare::ListDiff;
+use super::directory::Installer;
+use super::index::BacktraceIdx;
+use super::target::Target;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Kind of a build change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ChangeKind {
+    TargetAdded,
+    TargetRemoved,
+    TypeChanged,
+    DependencyAdded,
+    DependencyRemoved,
+    SourceAdded,
+    SourceRemoved,
+    FlagAdded,
+    FlagRemoved,
+    DefineAdded,
+    DefineRemoved,
+    IncludeAdded,
+    IncludeRemoved,
+    LinkLibraryAdded,
+    LinkLibraryRemoved,
+    InstallRuleAdded,
+    InstallRuleRemoved,
+}
+
+impl ChangeKind {
+    /// Human readable description, e.g. `source added`
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            ChangeKind::TargetAdded => "target added",
+            ChangeKind::TargetRemoved => "target removed",
+            ChangeKind::TypeChanged => "type changed",
+            ChangeKind::DependencyAdded => "dependency added",
+            ChangeKind::DependencyRemoved => "dependency removed",
+            ChangeKind::SourceAdded => "source added",
+            ChangeKind::SourceRemoved => "source removed",
+            ChangeKind::FlagAdded => "compile flag added",
+            ChangeKind::FlagRemoved => "compile flag removed",
+            ChangeKind::DefineAdded => "define added",
+            ChangeKind::DefineRemoved => "define removed",
+            ChangeKind::IncludeAdded => "include directory added",
+            ChangeKind::IncludeRemoved => "include directory removed",
+            ChangeKind::LinkLibraryAdded => "link library added",
+            ChangeKind::LinkLibraryRemoved => "link library removed",
+            ChangeKind::InstallRuleAdded => "install rule added",
+            ChangeKind::InstallRuleRemoved => "install rule removed",
+        }
+    }
+}
+
+/// A single build change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Change {
+    pub kind: ChangeKind,
+
+    /// Name of the changed target, `None` for install rules.
+    pub target: Option<String>,
+
+    /// Changed value, e.g. the source path or `EXECUTABLE -> SHARED_LIBRARY` for type changes.
+    pub value: String,
+
+    /// Backtrace in the new codemodel, innermost frame first.
+    /// For removed items this is the backtrace of the target if it still exists.
+    pub backtrace: Vec<BacktraceFrame>,
+}
+
+/// Semantic difference between two codemodels, e.g. before and after a change of a CMakeLists.txt
+///
+/// Targets are lined up by name.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CodeModelDiff {
+    /// Name of the compared configuration.
+    pub configuration: String,
+
+    pub changes: Vec<Change>,
+}
+
+impl CodeModelDiff {
+    /// Compare a configuration of two codemodels
+    ///
+    /// Without a configuration name the first configuration of each codemodel is compared.
+    /// Returns `None` if the configuration does not exist in both codemodels.
+    #[must_use]
+    pub fn new(old: &CodeModel, new: &CodeModel, configuration: Option<&str>) -> Option<Self> {
+        let old_config = find_configuration(old, configuration)?;
+        let new_config = find_configuration(new, configuration)?;
+
+        let mut diff = CodeModelDiff {
+            configuration: new_config.name.clone(),
+            changes: Vec::new(),
+        };
+
+        for target in &old_config.targets {
+            if new_config.targets.iter().all(|t| t.name != target.name) {
+                diff.push(
+                    ChangeKind::TargetRemoved,
+                    Some(target),
+                    &target.name,
+                    Vec::new(),
+                );
+            }
+        }
+        for new_target in &new_config.targets {
+            match old_config
+                .targets
+                .iter()
+                .find(|target| target.name == new_target.name)
+            {
+                Some(old_target) => {
+                    diff.diff_targets(old_config, old_target, new_config, new_target)
+                }
+                None => diff.push(
+                    ChangeKind::TargetAdded,
+                    Some(new_target),
+                    &new_target.name,
+                    target_backtrace(new_target, new_target.backtrace),
+                ),
+            }
+        }
+
+        diff.diff_installers(old_config, new_config);
+        Some(diff)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Render as JSON
+    ///
+    /// # Errors
+    ///
+    /// `serde_json::Error`: if serialization fails
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Render as Markdown, e.g. for a review comment
+    ///
+    /// Changes are grouped by target, install rules are listed last.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("## Build changes ({})\n", self.configuration);
+        if self.changes.is_empty() {
+            markdown.push_str("\nNo changes.\n");
+            return markdown;
+        }
+
+        let mut sections: Vec<Option<&str>> = Vec::new();
+        for change in &self.changes {
+            if !sections.contains(&change.target.as_deref()) {
+                sections.push(change.target.as_deref());
+            }
+        }
+
+        for section in sections {
+            match section {
+                Some(target) => {
+                    let _ = write!(markdown, "\n### `{target}`\n\n");
+                }
+                None => markdown.push_str("\n### Install rules\n\n"),
+            }
+            for change in self
+                .changes
+                .iter()
+                .filter(|change| change.target.as_deref() == section)
+            {
+                let _ = write!(
+                    markdown,
+                    "- {}: `{}`",
+                    change.kind.description(),
+                    change.value
+                );
+                if let Some(frame) = change.backtrace.first() {
+                    let _ = write!(markdown, " ({frame})");
+                }
+                markdown.push('\n');
+            }
+        }
+        markdown
+    }
+
+    fn push(
+        &mut self,
+        kind: ChangeKind,
+        target: Option<&Target>,
+        value: &str,
+        backtrace: Vec<BacktraceFrame>,
+    ) {
+        self.changes.push(Change {
+            kind,
+            target: target.map(|t| t.name.clone()),
+            value: value.to_owned(),
+            backtrace,
+        });
+    }
+
+    fn push_list(
+        &mut self,
+        (added, removed): (ChangeKind, ChangeKind),
+        target: &Target,
+        list: &ListDiff,
+        backtrace_of: impl Fn(&str) -> Option<BacktraceIdx>,
+    ) {
+        for value in &list.removed {
+            self.push(
+                removed,
+                Some(target),
+                value,
+                target_backtrace(target, target.backtrace),
+            );
+        }
+        for value in &list.added {
+            let backtrace = backtrace_of(value).or(target.backtrace);
+            self.push(
+                added,
+                Some(target),
+                value,
+                target_backtrace(target, backtrace),
+            );
+        }
+    }
+
+    fn diff_targets(
+        &mut self,
+        old_config: &Configuration,
+        old: &Target,
+        new_config: &Configuration,
+        new: &Target,
+    ) {
+        if old.type_name != new.type_name {
+            self.push(
+                ChangeKind::TypeChanged,
+                Some(new),
+                &format!("{} -> {}", old.type_name, new.type_name),
+                target_backtrace(new, new.backtrace),
+            );
+        }
+
+        let dependencies = |config: &Configuration, target: &Target| -> Vec<String> {
+            target
+                .dependencies
+                .iter()
+                .map(|dependency| target_name(config, &dependency.id))
+                .collect()
+        };
+        self.push_list(
+            (ChangeKind::DependencyAdded, ChangeKind::DependencyRemoved),
+            new,
+            &ListDiff::new(
+                &dependencies(old_config, old),
+                &dependencies(new_config, new),
+            ),
+            |name| {
+                new.dependencies
+                    .iter()
+                    .find(|dependency| target_name(new_config, &dependency.id) == name)
+                    .and_then(|dependency| dependency.backtrace)
+            },
+        );
+
+        let sources = |target: &Target| -> Vec<String> {
+            target
+                .sources
+                .iter()
+                .map(|source| source.path.display().to_string())
+                .collect()
+        };
+        self.push_list(
+            (ChangeKind::SourceAdded, ChangeKind::SourceRemoved),
+            new,
+            &ListDiff::new(&sources(old), &sources(new)),
+            |path| {
+                new.sources
+                    .iter()
+                    .find(|source| source.path.display().to_string() == path)
+                    .and_then(|source| source.backtrace)
+            },
+        );
+
+        let flags = |target: &Target| -> Vec<String> {
+            let mut flags: Vec<String> = Vec::new();
+            for flag in target
+                .compile_groups
+                .iter()
+                .flat_map(|group| group.compile_fragments())
+            {
+                if !flags.contains(&flag) {
+                    flags.push(flag);
+                }
+            }
+            flags
+        };
+        self.push_list(
+            (ChangeKind::FlagAdded, ChangeKind::FlagRemoved),
+            new,
+            &ListDiff::new(&flags(old), &flags(new)),
+            |_| None,
+        );
+
+        let defines = |target: &Target| -> Vec<String> {
+            let mut defines: Vec<String> = Vec::new();
+            for define in target
+                .compile_groups
+                .iter()
+                .flat_map(|group| &group.defines)
+            {
+                if !defines.contains(&define.define) {
+                    defines.push(define.define.clone());
+                }
+            }
+            defines
+        };
+        self.push_list(
+            (ChangeKind::DefineAdded, ChangeKind::DefineRemoved),
+            new,
+            &ListDiff::new(&defines(old), &defines(new)),
+            |value| {
+                new.compile_groups
+                    .iter()
+                    .flat_map(|group| &group.defines)
+                    .find(|define| define.define == value)
+                    .and_then(|define| define.backtrace)
+            },
+        );
+
+        let includes = |target: &Target| -> Vec<String> {
+            let mut includes: Vec<String> = Vec::new();
+            for include in target
+                .compile_groups
+                .iter()
+                .flat_map(|group| &group.includes)
+            {
+                let path = include.path.display().to_string();
+                if !includes.contains(&path) {
+                    includes.push(path);
+                }
+            }
+            includes
+        };
+        self.push_list(
+            (ChangeKind::IncludeAdded, ChangeKind::IncludeRemoved),
+            new,
+            &ListDiff::new(&includes(old), &includes(new)),
+            |path| {
+                new.compile_groups
+                    .iter()
+                    .flat_map(|group| &group.includes)
+                    .find(|include| include.path.display().to_string() == path)
+                    .and_then(|include| include.backtrace)
+            },
+        );
+
+        let libraries = |target: &Target| -> Vec<String> {
+            target
+                .link
+                .iter()
+                .flat_map(|link| &link.command_fragments)
+                .filter(|fragment| fragment.role == "libraries")
+                .map(|fragment| fragment.fragment.clone())
+                .collect()
+        };
+        self.push_list(
+            (ChangeKind::LinkLibraryAdded, ChangeKind::LinkLibraryRemoved),
+            new,
+            &ListDiff::new(&libraries(old), &libraries(new)),
+            |_| None,
+        );
+    }
+
+    fn diff_installers(&mut self, old_config: &Configuration, new_config: &Configuration) {
+        let rules = |config: &Configuration| -> Vec<String> {
+            config
+                .directories
+                .iter()
+                .flat_map(|directory| &directory.installers)
+                .map(|installer| describe_installer(config, installer))
+                .collect()
+        };
+        let list = ListDiff::new(&rules(old_config), &rules(new_config));
+
+        for value in &list.removed {
+            self.push(ChangeKind::InstallRuleRemoved, None, value, Vec::new());
+        }
+        for value in &list.added {
+            let backtrace = new_config
+                .directories
+                .iter()
+                .find_map(|directory| {
+                    let installer = directory
+                        .installers
+                        .iter()
+                        .find(|installer| &describe_installer(new_config, installer) == value)?;
+                    Some(backtrace(&directory.backtrace_graph, installer.backtrace))
+                })
+                .unwrap_or_default();
+            self.push(ChangeKind::InstallRuleAdded, None, value, backtrace);
+        }
+    }
+}
+
+impl CodeModel {
+    /// Semantic difference to a newer codemodel, see [`CodeModelDiff::new`]
+    #[must_use]
+    pub fn diff(&self, new: &CodeModel, configuration: Option<&str>) -> Option<CodeModelDiff> {
+        CodeModelDiff::new(self, new, configuration)
+    }
+}
+
+fn find_configuration<'a>(
+    codemodel: &'a CodeModel,
+    name: Option<&str>,
+) -> Option<&'a Configuration> {
+    match name {
+        Some(configuration) => codemodel
+            .configurations
+            .iter()
+            .find(|config| config.name == configuration),
+        None => codemodel.configurations.first(),
+    }
+}
+
+fn backtrace(graph: &BacktraceGraph, node: Option<BacktraceIdx>) -> Vec<BacktraceFrame> {
+    node.map(|index| graph.frames(index)).unwrap_or_default()
+}
+
+fn target_backtrace(target: &Target, node: Option<BacktraceIdx>) -> Vec<BacktraceFrame> {
+    backtrace(&target.backtrace_graph, node)
+}
+
+fn target_name(config: &Configuration, id: &str) -> String {
+    config
+        .targets
+        .iter()
+        .find(|target| target.id == id)
+        .map_or_else(|| id.to_owned(), |target| target.name.clone())
+}
+
+/// Stable description of an install rule, e.g. `target app -> bin`
+fn describe_installer(config: &Configuration, installer: &Installer) -> String {
+    let subject = match (&installer.target_id, &installer.export_name) {
+        (Some(id), _) => target_name(config, id),
+        (None, Some(export)) => export.clone(),
+        (None, None) => installer
+            .paths
+            .iter()
+            .map(|path| match path {
+                super::InstallPath::PathCombination(file) => file.clone(),
+                super::InstallPath::FromTo(from_to) => from_to.from.display().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let mut description = format!("{} {subject}", installer.installer_type);
+    if let Some(destination) = &installer.destination {
+        let _ = write!(description, " -> {destination}");
+    }
+    if !installer.component.is_empty() {
+        let _ = write!(description, " [{}]", installer.component);
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::diff::*;
+    use serde_json::json;
+
+    fn codemodel(targets: serde_json::Value) -> CodeModel {
+        let mut codemodel = CodeModel::default();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: serde_json::from_value(targets).unwrap(),
+            ..Default::default()
+        });
+        codemodel
+    }
+
+    #[test]
+    fn test_diff() {
+        let graph = json!({
+            "commands": [ "add_executable", "target_compile_definitions" ],
+            "files": [ "CMakeLists.txt" ],
+            "nodes": [
+                { "file": 0 },
+                { "command": 0, "file": 0, "line": 3, "parent": 0 },
+                { "command": 1, "file": 0, "line": 4, "parent": 0 }
+            ]
+        });
+        let old = codemodel(json!([
+            {
+                "name": "app", "id": "app::@1", "type": "EXECUTABLE", "backtrace": 1,
+                "directoryIndex": 0, "projectIndex": 0, "paths": { "source": ".", "build": "." },
+                "sources": [ { "path": "main.cpp", "backtrace": 1 } ],
+                "backtraceGraph": graph
+            },
+            {
+                "name": "old", "id": "old::@1", "type": "STATIC_LIBRARY",
+                "directoryIndex": 0, "projectIndex": 0, "paths": { "source": ".", "build": "." },
+                "backtraceGraph": graph
+            }
+        ]));
+        let new = codemodel(json!([
+            {
+                "name": "app", "id": "app::@1", "type": "EXECUTABLE", "backtrace": 1,
+                "directoryIndex": 0, "projectIndex": 0, "paths": { "source": ".", "build": "." },
+                "sources": [ { "path": "main.cpp", "backtrace": 1 }, { "path": "extra.cpp", "backtrace": 1 } ],
+                "compileGroups": [{
+                    "language": "CXX",
+                    "sourceIndexes": [0, 1],
+                    "defines": [ { "define": "FEATURE=1", "backtrace": 2 } ]
+                }],
+                "backtraceGraph": graph
+            }
+        ]));
+
+        let diff = old.diff(&new, None).unwrap();
+        let kinds: Vec<ChangeKind> = diff.changes.iter().map(|change| change.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::TargetRemoved,
+                ChangeKind::SourceAdded,
+                ChangeKind::DefineAdded
+            ]
+        );
+        assert_eq!(diff.changes[2].backtrace[0].line, Some(4));
+
+        assert_eq!(
+            diff.to_markdown(),
+            "## Build changes (Debug)
+
+### `old`
+
+- target removed: `old`
+
+### `app`
+
+- source added: `extra.cpp` (CMakeLists.txt:3 (add_executable))
+- define added: `FEATURE=1` (CMakeLists.txt:4 (target_compile_definitions))
+"
+        );
+        assert!(diff.to_json().unwrap().contains("\"defineAdded\""));
+    }
+}
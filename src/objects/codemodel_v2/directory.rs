@@ -2,6 +2,7 @@
 #![allow(clippy::module_name_repetitions)]
 
 use super::backtrace_graph::BacktraceGraph;
+use super::index::{BacktraceIdx, InstallerIdx, TargetIdx};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -18,6 +19,11 @@ pub struct Directory {
 
     /// Entries corresponding to install() rules
     pub installers: Vec<Installer>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +41,11 @@ pub struct DirectoryPaths {
     /// relative to that directory (with . for the top-level build directory itself).
     /// Otherwise, the path is absolute.
     pub source: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -62,7 +73,9 @@ pub struct Installer {
     /// * importedRuntimeArtifacts: An install(IMPORTED_RUNTIME_ARTIFACTS) call. The destination member is populated. The isOptional member may exist. This type has no additional members.
     /// * runtimeDependencySet: An install(RUNTIME_DEPENDENCY_SET) call or an install(TARGETS) call with RUNTIME_DEPENDENCIES. The destination member is populated. This type has additional members runtimeDependencySetName and runtimeDependencySetType.
     /// * fileSet: An install(TARGETS) call with FILE_SET. The destination and paths members are populated. The isOptional member may exist. This type has additional members fileSetName, fileSetType, fileSetDirectories, and fileSetTarget.
-    /// This type was added in codemodel version 2.4.
+    ///   This type was added in codemodel version 2.4.
+    /// * cxxModuleBmi: An install(TARGETS) call with CXX_MODULES_BMI. The destination member is populated and the isOptional member may exist. This type has an additional cxxModuleBmiTarget member.
+    ///   This type was added in codemodel version 2.5.
     #[serde(rename = "type")]
     pub installer_type: String,
 
@@ -85,7 +98,7 @@ pub struct Installer {
 
     /// Optional member that is present when type is target.
     /// The value is an unsigned integer 0-based index into the main "codemodel" object's targets array for the target to be installed.
-    pub target_index: Option<usize>,
+    pub target_index: Option<TargetIdx>,
 
     /// True when type is target and the installer is for a Windows DLL import library file or for an AIX linker import file.
     #[serde(default)]
@@ -135,6 +148,11 @@ pub struct Installer {
     /// This field was added in codemodel version 2.4.
     pub file_set_target: Option<TargetIdAndIndex>,
 
+    /// Optional member that is present when type is cxxModuleBmi.
+    /// The value identifies the target whose C++ module BMIs are installed.
+    /// This field was added in codemodel version 2.5.
+    pub cxx_module_bmi_target: Option<TargetIdAndIndex>,
+
     /// Optional member that is present when type is script.
     /// The value is a string specifying the path to the script file on disk, represented with forward slashes.
     /// If the file is inside the top-level source directory then the path is specified relative to that directory.
@@ -144,7 +162,12 @@ pub struct Installer {
     /// Optional member that is present when a CMake language backtrace to the install() or other command invocation
     /// that added this installer is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -155,7 +178,20 @@ pub struct TargetIdAndIndex {
     pub id: String,
 
     /// An unsigned integer 0-based index into the main "codemodel" object's targets array for the target.
-    pub index: usize,
+    pub index: TargetIdx,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+impl Directory {
+    /// Installer by index, `None` if the index is out of range
+    #[must_use]
+    pub fn installer(&self, index: InstallerIdx) -> Option<&Installer> {
+        self.installers.get(index.get())
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -167,6 +203,11 @@ pub struct FromToPaths {
 
     /// Path to which the file or directory is to be installed under the destination.
     pub to: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -216,8 +257,10 @@ mod tests {
                 installers: vec![],
                 paths: DirectoryPaths {
                     build: PathBuf::from("."),
-                    source: PathBuf::from(".")
-                }
+                    source: PathBuf::from("."),
+                    ..Default::default()
+                },
+                ..Default::default()
             }
         );
     }
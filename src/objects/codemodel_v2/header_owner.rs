@@ -0,0 +1,371 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::{CodeModel, Configuration};
+use super::index::{CompileGroupIdx, TargetIdx};
+use super::target::Target;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Why a target was chosen as owner of a header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum HeaderOwnerReason {
+    /// The file is listed in the sources of the target.
+    ListedSource,
+
+    /// The file is located below a base directory of a HEADERS file set of the target.
+    FileSet {
+        name: String,
+        base_directory: PathBuf,
+    },
+
+    /// The file is located below a (non-system) include directory of the target.
+    /// The closest include directory wins.
+    IncludeDirectory { directory: PathBuf },
+
+    /// The target compiles a source file in the same directory or the nearest parent directory.
+    SiblingSource { source: PathBuf },
+}
+
+/// Best guess of the target and compile group for a header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct HeaderOwner {
+    /// Name of the configuration, e.g. Debug.
+    pub configuration: String,
+
+    /// Name of the owning target.
+    pub target: String,
+
+    /// 0-based index into the targets of the configuration.
+    pub target_index: TargetIdx,
+
+    /// 0-based index into the compile groups of the target whose flags should be used for the header.
+    pub compile_group_index: Option<CompileGroupIdx>,
+
+    /// Why the target was chosen.
+    pub reason: HeaderOwnerReason,
+}
+
+impl CodeModel {
+    /// Infer the owning target of a header (or any file) in the first configuration
+    ///
+    /// Relative paths are resolved against the top-level source directory. Paths are compared
+    /// case-insensitively on Windows and macOS, like in [`super::SourceIndex`].
+    /// The heuristics are tried in order:
+    /// 1. the file is listed in the sources of a target
+    /// 2. HEADERS file set membership (closest base directory)
+    /// 3. include directories (closest non-system include directory)
+    /// 4. nearest sibling source file compiled by a target
+    #[must_use]
+    pub fn header_owner<P: AsRef<Path>>(&self, header: P) -> Option<HeaderOwner> {
+        let config = self.configurations.first()?;
+        self.header_owner_for(config, header.as_ref(), paths::host_case_sensitive())
+    }
+
+    /// Infer the owning target of a header in the given configuration, see [`CodeModel::header_owner`]
+    #[must_use]
+    pub fn header_owner_in<P: AsRef<Path>>(
+        &self,
+        configuration: &str,
+        header: P,
+    ) -> Option<HeaderOwner> {
+        let config = self
+            .configurations
+            .iter()
+            .find(|config| config.name == configuration)?;
+        self.header_owner_for(config, header.as_ref(), paths::host_case_sensitive())
+    }
+
+    fn header_owner_for(
+        &self,
+        config: &Configuration,
+        path: &Path,
+        case_sensitive: bool,
+    ) -> Option<HeaderOwner> {
+        let source_dir = &self.paths.source;
+        // resolved and normalized path, compared with the case sensitivity of the file system
+        let key = |file: &Path| paths::case_key(&paths::resolve(source_dir, file), case_sensitive);
+        let header = key(path);
+
+        let owner = |index: usize, compile_group_index: Option<CompileGroupIdx>, reason| {
+            let target_index = TargetIdx::new(index);
+            let target = config.target(target_index)?;
+            Some(HeaderOwner {
+                configuration: config.name.clone(),
+                target: target.name.clone(),
+                target_index,
+                compile_group_index: compile_group_index.or_else(|| primary_compile_group(target)),
+                reason,
+            })
+        };
+
+        // 1. listed source
+        for (target_index, target) in config.targets.iter().enumerate() {
+            if let Some(source) = target
+                .sources
+                .iter()
+                .find(|source| key(&source.path) == header)
+            {
+                return owner(
+                    target_index,
+                    source.compile_group_index,
+                    HeaderOwnerReason::ListedSource,
+                );
+            }
+        }
+
+        // 2. file sets
+        let mut best: Option<(usize, usize, HeaderOwnerReason)> = None;
+        for (target_index, target) in config.targets.iter().enumerate() {
+            for file_set in target
+                .file_sets
+                .iter()
+                .filter(|file_set| file_set.type_name == "HEADERS")
+            {
+                for base_directory in &file_set.base_directories {
+                    let dir = key(Path::new(base_directory));
+                    if paths::starts_with(&header, &dir)
+                        && best.as_ref().map_or(true, |(len, _, _)| dir.len() > *len)
+                    {
+                        best = Some((
+                            dir.len(),
+                            target_index,
+                            HeaderOwnerReason::FileSet {
+                                name: file_set.name.clone(),
+                                base_directory: PathBuf::from(base_directory),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some((_, target_index, reason)) = best {
+            return owner(target_index, None, reason);
+        }
+
+        // 3. include directories
+        let mut best_include: Option<(usize, usize, usize, PathBuf)> = None;
+        for (target_index, target) in config.targets.iter().enumerate() {
+            for (group_index, group) in target.compile_groups.iter().enumerate() {
+                for include in group.includes.iter().filter(|include| !include.is_system) {
+                    let dir = key(&include.path);
+                    if paths::starts_with(&header, &dir)
+                        && best_include
+                            .as_ref()
+                            .map_or(true, |(len, _, _, _)| dir.len() > *len)
+                    {
+                        best_include =
+                            Some((dir.len(), target_index, group_index, include.path.clone()));
+                    }
+                }
+            }
+        }
+        if let Some((_, target_index, group_index, directory)) = best_include {
+            return owner(
+                target_index,
+                Some(CompileGroupIdx::new(group_index)),
+                HeaderOwnerReason::IncludeDirectory { directory },
+            );
+        }
+
+        // 4. nearest sibling source
+        let mut dir = Path::new(&header).parent();
+        while let Some(current) = dir {
+            let current_dir = key(current);
+            for (target_index, target) in config.targets.iter().enumerate() {
+                for source in target
+                    .sources
+                    .iter()
+                    .filter(|source| source.compile_group_index.is_some())
+                {
+                    let source_path = key(&source.path);
+                    if Path::new(&source_path)
+                        .parent()
+                        .map_or(false, |parent| key(parent) == current_dir)
+                    {
+                        return owner(
+                            target_index,
+                            source.compile_group_index,
+                            HeaderOwnerReason::SiblingSource {
+                                source: source.path.clone(),
+                            },
+                        );
+                    }
+                }
+            }
+            dir = current.parent();
+        }
+
+        None
+    }
+}
+
+/// Compile group used for headers: CXX, then C, then the first one
+fn primary_compile_group(target: &Target) -> Option<CompileGroupIdx> {
+    let position = |language: &str| {
+        target
+            .compile_groups
+            .iter()
+            .position(|group| group.language == language)
+    };
+    position("CXX")
+        .or_else(|| position("C"))
+        .or_else(|| (!target.compile_groups.is_empty()).then_some(0))
+        .map(CompileGroupIdx::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::header_owner::*;
+    use crate::objects::codemodel_v2::{CompileGroup, FileSet, Include, Source};
+
+    #[test]
+    fn test_header_owner() {
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/project".into();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![
+                Target {
+                    name: "core".into(),
+                    file_sets: vec![FileSet {
+                        name: "HEADERS".into(),
+                        type_name: "HEADERS".into(),
+                        visibility: "PUBLIC".into(),
+                        base_directories: vec!["core/include".into()],
+                        ..Default::default()
+                    }],
+                    sources: vec![
+                        Source {
+                            path: "core/src/core.c".into(),
+                            compile_group_index: Some(0.into()),
+                            ..Default::default()
+                        },
+                        Source {
+                            path: "core/src/listed.h".into(),
+                            ..Default::default()
+                        },
+                    ],
+                    compile_groups: vec![CompileGroup {
+                        language: "C".into(),
+                        source_indexes: vec![0.into()],
+                        includes: vec![Include {
+                            path: "/project".into(),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Target {
+                    name: "app".into(),
+                    sources: vec![Source {
+                        path: "app/main.cpp".into(),
+                        compile_group_index: Some(1.into()),
+                        ..Default::default()
+                    }],
+                    compile_groups: vec![
+                        CompileGroup {
+                            language: "C".into(),
+                            ..Default::default()
+                        },
+                        CompileGroup {
+                            language: "CXX".into(),
+                            source_indexes: vec![0.into()],
+                            includes: vec![
+                                Include {
+                                    path: "/project/app/include".into(),
+                                    ..Default::default()
+                                },
+                                Include {
+                                    path: "/usr/include".into(),
+                                    is_system: true,
+                                    ..Default::default()
+                                },
+                            ],
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+
+        let listed = codemodel.header_owner("core/src/listed.h").unwrap();
+        assert_eq!(listed.target, "core");
+        assert_eq!(listed.compile_group_index, Some(0.into()));
+        assert_eq!(listed.reason, HeaderOwnerReason::ListedSource);
+
+        let file_set = codemodel
+            .header_owner("/project/core/include/core/api.h")
+            .unwrap();
+        assert_eq!(file_set.target, "core");
+        assert_eq!(
+            file_set.reason,
+            HeaderOwnerReason::FileSet {
+                name: "HEADERS".into(),
+                base_directory: "core/include".into()
+            }
+        );
+
+        // closest include directory wins over `/project`
+        let include = codemodel.header_owner("app/include/app.hpp").unwrap();
+        assert_eq!(include.target, "app");
+        assert_eq!(include.compile_group_index, Some(1.into()));
+        assert_eq!(
+            include.reason,
+            HeaderOwnerReason::IncludeDirectory {
+                directory: "/project/app/include".into()
+            }
+        );
+
+        // system include directories are ignored
+        assert_eq!(codemodel.header_owner("/usr/include/stdio.h"), None);
+
+        let mut without_includes = codemodel.clone();
+        without_includes.configurations[0].targets[0].compile_groups[0]
+            .includes
+            .clear();
+        let sibling = without_includes
+            .header_owner_in("Debug", "app/detail/impl.hpp")
+            .unwrap();
+        assert_eq!(sibling.target, "app");
+        assert_eq!(
+            sibling.reason,
+            HeaderOwnerReason::SiblingSource {
+                source: "app/main.cpp".into()
+            }
+        );
+
+        // case-insensitive file systems
+        let config = &codemodel.configurations[0];
+        let header = Path::new("Core/Include/api.h");
+        assert_eq!(
+            codemodel
+                .header_owner_for(config, header, true)
+                .unwrap()
+                .reason,
+            HeaderOwnerReason::IncludeDirectory {
+                directory: "/project".into()
+            }
+        );
+        let insensitive = codemodel.header_owner_for(config, header, false).unwrap();
+        assert_eq!(insensitive.target, "core");
+        assert_eq!(
+            insensitive.reason,
+            HeaderOwnerReason::FileSet {
+                name: "HEADERS".into(),
+                base_directory: "core/include".into()
+            }
+        );
+        let listed_insensitive = codemodel
+            .header_owner_for(config, Path::new("/PROJECT/core/src/Listed.h"), false)
+            .unwrap();
+        assert_eq!(listed_insensitive.reason, HeaderOwnerReason::ListedSource);
+    }
+}
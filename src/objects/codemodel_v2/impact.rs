@@ -0,0 +1,349 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::CodeModel;
+use super::index::TargetIdx;
+use crate::objects::cmake_files_v1::CMakeFiles;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Why a target is affected by a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ImpactReason {
+    /// A changed file is listed in the sources of the target.
+    Source { path: PathBuf },
+
+    /// A changed file is located below a base directory of a file set of the target.
+    FileSet { name: String, path: PathBuf },
+
+    /// A changed file is located below an include directory of the target.
+    IncludeDirectory { directory: PathBuf, path: PathBuf },
+
+    /// A changed file is an input of the `CMake` configuration, which affects every target.
+    CMakeInput { path: PathBuf },
+
+    /// The target depends on an affected target.
+    Dependency { target: String },
+}
+
+/// A target affected by a change.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AffectedTarget {
+    /// Name of the target.
+    pub name: String,
+
+    /// Unique id of the target.
+    pub id: String,
+
+    /// Type of the target, e.g. EXECUTABLE.
+    pub type_name: String,
+
+    /// Index into the targets of the configuration.
+    pub target_index: TargetIdx,
+
+    /// Reasons why the target is affected.
+    pub reasons: Vec<ImpactReason>,
+}
+
+/// Targets affected by a set of changed files.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Impact {
+    /// Name of the configuration, e.g. Debug.
+    pub configuration: String,
+
+    /// Targets that use a changed file directly.
+    pub direct: Vec<AffectedTarget>,
+
+    /// Targets that are affected only through their dependencies.
+    pub transitive: Vec<AffectedTarget>,
+}
+
+impl Impact {
+    /// All affected targets, direct ones first
+    pub fn all(&self) -> impl Iterator<Item = &AffectedTarget> {
+        self.direct.iter().chain(&self.transitive)
+    }
+
+    /// Affected executables, e.g. to select tests to re-run
+    #[must_use]
+    pub fn executables(&self) -> Vec<&AffectedTarget> {
+        self.all()
+            .filter(|target| target.type_name == "EXECUTABLE")
+            .collect()
+    }
+
+    /// True if a target with the given name is affected
+    #[must_use]
+    pub fn is_affected(&self, name: &str) -> bool {
+        self.all().any(|target| target.name == name)
+    }
+}
+
+impl CodeModel {
+    /// Targets of a configuration that must be rebuilt because of changed files
+    ///
+    /// Changed files are mapped to targets through their sources, file sets and include directories.
+    /// If `cmake_files` is given, a changed `CMake` input (e.g. a CMakeLists.txt) affects every target.
+    /// Affected targets are propagated to their dependents through the reverse dependency graph.
+    /// Relative paths are resolved against the top-level source directory.
+    ///
+    /// Returns `None` if the configuration does not exist.
+    #[must_use]
+    pub fn impact<P: AsRef<Path>>(
+        &self,
+        configuration: &str,
+        changed_files: &[P],
+        cmake_files: Option<&CMakeFiles>,
+    ) -> Option<Impact> {
+        let config = self
+            .configurations
+            .iter()
+            .find(|config| config.name == configuration)?;
+        let source_dir = &self.paths.source;
+
+        let changed: Vec<(String, &Path)> = changed_files
+            .iter()
+            .map(|path| (paths::resolve(source_dir, path.as_ref()), path.as_ref()))
+            .collect();
+
+        let cmake_inputs: Vec<PathBuf> = cmake_files.map_or_else(Vec::new, |files| {
+            files
+                .inputs
+                .iter()
+                .filter(|input| !input.is_cmake)
+                .filter(|input| {
+                    let input_path = paths::resolve(&files.paths.source, &input.path);
+                    changed.iter().any(|(path, _)| *path == input_path)
+                })
+                .map(|input| input.path.clone())
+                .collect()
+        });
+
+        let mut affected: Vec<Option<AffectedTarget>> = vec![None; config.targets.len()];
+        for (target_index, target) in config.targets.iter().enumerate() {
+            let mut reasons: Vec<ImpactReason> = cmake_inputs
+                .iter()
+                .map(|path| ImpactReason::CMakeInput { path: path.clone() })
+                .collect();
+
+            for (path, changed_file) in &changed {
+                let original = changed_file.to_path_buf();
+                if target
+                    .sources
+                    .iter()
+                    .any(|source| paths::resolve(source_dir, &source.path) == *path)
+                {
+                    reasons.push(ImpactReason::Source { path: original });
+                    continue;
+                }
+
+                if let Some(file_set) = target.file_sets.iter().find(|file_set| {
+                    file_set.base_directories.iter().any(|dir| {
+                        paths::starts_with(path, &paths::resolve(source_dir, Path::new(dir)))
+                    })
+                }) {
+                    reasons.push(ImpactReason::FileSet {
+                        name: file_set.name.clone(),
+                        path: original,
+                    });
+                    continue;
+                }
+
+                if let Some(include) = target
+                    .compile_groups
+                    .iter()
+                    .flat_map(|group| &group.includes)
+                    .find(|include| {
+                        paths::starts_with(path, &paths::resolve(source_dir, &include.path))
+                    })
+                {
+                    reasons.push(ImpactReason::IncludeDirectory {
+                        directory: include.path.clone(),
+                        path: original,
+                    });
+                }
+            }
+
+            if !reasons.is_empty() {
+                affected[target_index] = Some(AffectedTarget {
+                    name: target.name.clone(),
+                    id: target.id.clone(),
+                    type_name: target.type_name.clone(),
+                    target_index: TargetIdx::new(target_index),
+                    reasons,
+                });
+            }
+        }
+
+        // reverse dependency graph: dependency id -> dependents
+        let index_by_id: HashMap<&str, usize> = config
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| (target.id.as_str(), index))
+            .collect();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); config.targets.len()];
+        for (target_index, target) in config.targets.iter().enumerate() {
+            for dependency in &target.dependencies {
+                if let Some(&dependency_index) = index_by_id.get(dependency.id.as_str()) {
+                    dependents[dependency_index].push(target_index);
+                }
+            }
+        }
+
+        let direct_count = affected.iter().flatten().count();
+        let mut is_direct = vec![false; config.targets.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for (index, entry) in affected.iter().enumerate() {
+            if entry.is_some() {
+                is_direct[index] = true;
+                queue.push_back(index);
+            }
+        }
+
+        let mut transitive_order = Vec::new();
+        while let Some(index) = queue.pop_front() {
+            for &dependent in &dependents[index] {
+                if is_direct[dependent] {
+                    continue;
+                }
+                let reason = ImpactReason::Dependency {
+                    target: config.targets[index].name.clone(),
+                };
+                match &mut affected[dependent] {
+                    Some(entry) => entry.reasons.push(reason),
+                    entry @ None => {
+                        let target = &config.targets[dependent];
+                        *entry = Some(AffectedTarget {
+                            name: target.name.clone(),
+                            id: target.id.clone(),
+                            type_name: target.type_name.clone(),
+                            target_index: TargetIdx::new(dependent),
+                            reasons: vec![reason],
+                        });
+                        transitive_order.push(dependent);
+                        queue.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        let mut impact = Impact {
+            configuration: config.name.clone(),
+            direct: Vec::with_capacity(direct_count),
+            transitive: Vec::with_capacity(transitive_order.len()),
+        };
+        for (index, entry) in affected.iter_mut().enumerate() {
+            if is_direct[index] {
+                impact.direct.extend(entry.take());
+            }
+        }
+        for index in transitive_order {
+            impact.transitive.extend(affected[index].take());
+        }
+
+        Some(impact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::cmake_files_v1::Input;
+    use crate::objects::codemodel_v2::impact::*;
+    use crate::objects::codemodel_v2::Configuration;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_impact() {
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/project".into();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![
+                target(json!({
+                    "name": "core",
+                    "type": "STATIC_LIBRARY",
+                    "sources": [ { "path": "core/core.cpp" } ],
+                    "compileGroups": [{
+                        "language": "CXX",
+                        "sourceIndexes": [],
+                        "includes": [ { "path": "/project/core/include" } ]
+                    }]
+                })),
+                target(json!({
+                    "name": "net",
+                    "type": "STATIC_LIBRARY",
+                    "sources": [ { "path": "net/net.cpp" } ],
+                    "dependencies": [ { "id": "core::@1" } ]
+                })),
+                target(json!({
+                    "name": "app",
+                    "sources": [ { "path": "app/main.cpp" } ],
+                    "dependencies": [ { "id": "net::@1" } ]
+                })),
+                target(json!({
+                    "name": "tool",
+                    "sources": [ { "path": "tool/main.cpp" } ]
+                })),
+            ],
+            ..Default::default()
+        });
+
+        let impact = codemodel
+            .impact("Debug", &["core/include/core.h"], None)
+            .unwrap();
+        assert_eq!(impact.direct.len(), 1);
+        assert_eq!(impact.direct[0].name, "core");
+        assert_eq!(
+            impact.direct[0].reasons,
+            vec![ImpactReason::IncludeDirectory {
+                directory: "/project/core/include".into(),
+                path: "core/include/core.h".into()
+            }]
+        );
+        let transitive: Vec<&str> = impact
+            .transitive
+            .iter()
+            .map(|target| target.name.as_str())
+            .collect();
+        assert_eq!(transitive, vec!["net", "app"]);
+        assert_eq!(
+            impact.transitive[1].reasons,
+            vec![ImpactReason::Dependency {
+                target: "net".into()
+            }]
+        );
+        assert_eq!(impact.executables().len(), 1);
+        assert!(!impact.is_affected("tool"));
+
+        let impact_tool = codemodel
+            .impact("Debug", &["/project/tool/main.cpp"], None)
+            .unwrap();
+        assert_eq!(impact_tool.direct[0].name, "tool");
+        assert!(impact_tool.transitive.is_empty());
+
+        let mut cmake_files = CMakeFiles::default();
+        cmake_files.paths.source = "/project".into();
+        cmake_files.inputs.push(Input {
+            path: "CMakeLists.txt".into(),
+            ..Default::default()
+        });
+        let impact_cmake = codemodel
+            .impact("Debug", &["CMakeLists.txt"], Some(&cmake_files))
+            .unwrap();
+        assert_eq!(impact_cmake.direct.len(), 4);
+        assert!(impact_cmake.transitive.is_empty());
+
+        assert!(codemodel
+            .impact("Release", &["CMakeLists.txt"], None)
+            .is_none());
+    }
+}
@@ -0,0 +1,120 @@
+//! Typed indices into the arrays of the codemodel objects
+//!
+//! Each index type only addresses one kind of array, so using e.g. a source index against the compile groups
+//! of a target is a compile error. The indices serialize transparently as unsigned integers.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+macro_rules! index_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(usize);
+
+        impl $name {
+            #[must_use]
+            pub const fn new(index: usize) -> Self {
+                $name(index)
+            }
+
+            /// Underlying 0-based array index
+            #[must_use]
+            pub const fn get(self) -> usize {
+                self.0
+            }
+        }
+
+        impl From<usize> for $name {
+            fn from(index: usize) -> Self {
+                $name(index)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(index: $name) -> Self {
+                index.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+index_type!(
+    /// Index into the targets of a configuration
+    TargetIdx
+);
+
+index_type!(
+    /// Index into the directories of a configuration
+    DirectoryIdx
+);
+
+index_type!(
+    /// Index into the projects of a configuration
+    ProjectIdx
+);
+
+index_type!(
+    /// Index into the installers of a directory
+    InstallerIdx
+);
+
+index_type!(
+    /// Index into the sources of a target
+    SourceIdx
+);
+
+index_type!(
+    /// Index into the compile groups of a target
+    CompileGroupIdx
+);
+
+index_type!(
+    /// Index into the source groups of a target
+    SourceGroupIdx
+);
+
+index_type!(
+    /// Index into the file sets of a target
+    FileSetIdx
+);
+
+index_type!(
+    /// Index into the nodes of a backtrace graph
+    BacktraceIdx
+);
+
+index_type!(
+    /// Index into the files of a backtrace graph
+    BacktraceFileIdx
+);
+
+index_type!(
+    /// Index into the commands of a backtrace graph
+    BacktraceCommandIdx
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::index::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_index() {
+        let indices: Vec<SourceIdx> = serde_json::from_value(json!([0, 3])).unwrap();
+        assert_eq!(indices, vec![SourceIdx::new(0), SourceIdx::from(3)]);
+        assert_eq!(indices[1].get(), 3);
+        assert_eq!(usize::from(indices[1]), 3);
+        assert_eq!(indices[1].to_string(), "3");
+        assert_eq!(serde_json::to_value(&indices).unwrap(), json!([0, 3]));
+    }
+}
@@ -0,0 +1,421 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::{CodeModel, Configuration};
+use super::directory::{InstallPath, Installer};
+use crate::objects::relocation::PathBase;
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Type of the install() rule an entry originates from, see `Installer::installer_type`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum InstallKind {
+    Target,
+    File,
+    Directory,
+    Export,
+    Script,
+    Code,
+    ImportedRuntimeArtifacts,
+    RuntimeDependencySet,
+    FileSet,
+    CxxModuleBmi,
+    /// Installer type not known to this crate.
+    Other(String),
+}
+
+impl From<&str> for InstallKind {
+    fn from(installer_type: &str) -> Self {
+        match installer_type {
+            "target" => InstallKind::Target,
+            "file" => InstallKind::File,
+            "directory" => InstallKind::Directory,
+            "export" => InstallKind::Export,
+            "script" => InstallKind::Script,
+            "code" => InstallKind::Code,
+            "importedRuntimeArtifacts" => InstallKind::ImportedRuntimeArtifacts,
+            "runtimeDependencySet" => InstallKind::RuntimeDependencySet,
+            "fileSet" => InstallKind::FileSet,
+            "cxxModuleBmi" => InstallKind::CxxModuleBmi,
+            other => InstallKind::Other(other.to_owned()),
+        }
+    }
+}
+
+impl Installer {
+    /// Directory that the relative `paths` of the installer are relative to
+    ///
+    /// Paths of target, export and module installers are relative to the build directory,
+    /// paths of all other installers to the source directory.
+    #[must_use]
+    pub fn path_base(&self) -> PathBase {
+        match InstallKind::from(self.installer_type.as_str()) {
+            InstallKind::Target | InstallKind::Export | InstallKind::CxxModuleBmi => {
+                PathBase::Build
+            }
+            _ => PathBase::Source,
+        }
+    }
+}
+
+/// A single file or directory that would be installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InstallEntry {
+    /// Absolute path of the file or directory that is installed.
+    /// `None` for installers whose inputs are only known at install time (script, code, runtime dependencies).
+    pub source: Option<PathBuf>,
+
+    /// Absolute destination path below the install prefix.
+    /// For script and code installers this is `None`, for runtime dependencies it is the destination directory.
+    pub destination: Option<PathBuf>,
+
+    /// Type of the install() rule.
+    pub kind: InstallKind,
+
+    /// True if install() was called with OPTIONAL.
+    pub optional: bool,
+
+    /// Component of the install() rule.
+    pub component: String,
+
+    /// Name of the installed target for target installers.
+    pub target: Option<String>,
+}
+
+/// Predicted installation of a configuration.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InstallManifest {
+    /// Name of the configuration, e.g. Release.
+    pub configuration: String,
+
+    /// Installation prefix.
+    pub prefix: PathBuf,
+
+    /// Installed entries in the order of the install() rules.
+    pub entries: Vec<InstallEntry>,
+}
+
+impl InstallManifest {
+    /// Entries that install files or directories, i.e. have both source and destination
+    pub fn files(&self) -> impl Iterator<Item = &InstallEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.source.is_some() && entry.destination.is_some())
+    }
+}
+
+/// Options for simulating `cmake --install`.
+///
+/// Example:
+///
+/// ```
+/// use cmake_file_api::objects::codemodel_v2::InstallOptions;
+///
+/// let mut options = InstallOptions::new("/usr/local");
+/// options.configuration("Release").component("Runtime");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct InstallOptions {
+    prefix: PathBuf,
+    configuration: Option<String>,
+    components: Vec<String>,
+}
+
+impl InstallOptions {
+    pub fn new<P: AsRef<Path>>(prefix: P) -> Self {
+        InstallOptions {
+            prefix: prefix.as_ref().to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Select the configuration (defaults to the first configuration)
+    pub fn configuration(&mut self, name: &str) -> &mut Self {
+        self.configuration = Some(name.to_owned());
+        self
+    }
+
+    /// Restrict the installation to a component like `cmake --install --component`
+    ///
+    /// Can be called multiple times. Without components, every installer that is not EXCLUDE_FROM_ALL is used.
+    pub fn component(&mut self, name: &str) -> &mut Self {
+        self.components.push(name.to_owned());
+        self
+    }
+
+    /// Installation prefix
+    #[must_use]
+    pub fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+
+    pub(crate) fn select_configuration<'a>(
+        &self,
+        codemodel: &'a CodeModel,
+    ) -> Option<&'a Configuration> {
+        match &self.configuration {
+            Some(name) => codemodel
+                .configurations
+                .iter()
+                .find(|config| &config.name == name),
+            None => codemodel.configurations.first(),
+        }
+    }
+
+    fn is_selected(&self, installer: &Installer) -> bool {
+        if self.components.is_empty() {
+            !installer.is_exclude_from_all
+        } else {
+            installer.is_for_all_components || self.components.contains(&installer.component)
+        }
+    }
+}
+
+impl CodeModel {
+    /// Predict the installed tree without running `cmake --install`
+    ///
+    /// Expands the installers of all directories of the selected configuration.
+    /// Relative install destinations are placed below the prefix of the options.
+    /// Returns `None` if the configuration does not exist.
+    #[must_use]
+    pub fn install_manifest(&self, options: &InstallOptions) -> Option<InstallManifest> {
+        let config = options.select_configuration(self)?;
+
+        let mut manifest = InstallManifest {
+            configuration: config.name.clone(),
+            prefix: options.prefix.clone(),
+            entries: Vec::new(),
+        };
+
+        for installer in config
+            .directories
+            .iter()
+            .flat_map(|directory| &directory.installers)
+            .filter(|installer| options.is_selected(installer))
+        {
+            manifest
+                .entries
+                .extend(self.expand_installer(config, installer, &options.prefix));
+        }
+
+        Some(manifest)
+    }
+
+    pub(crate) fn expand_installer(
+        &self,
+        config: &Configuration,
+        installer: &Installer,
+        prefix: &Path,
+    ) -> Vec<InstallEntry> {
+        let kind = InstallKind::from(installer.installer_type.as_str());
+        let destination_dir = installer
+            .destination
+            .as_ref()
+            .map(|destination| PathBuf::from(paths::resolve(prefix, Path::new(destination))));
+        let installed_target = installer
+            .target_index
+            .and_then(|index| config.target(index));
+
+        let entry = |source: Option<PathBuf>, destination: Option<PathBuf>| InstallEntry {
+            source,
+            destination,
+            kind: kind.clone(),
+            optional: installer.is_optional,
+            component: installer.component.clone(),
+            target: installed_target.map(|target| target.name.clone()),
+        };
+
+        let base = match installer.path_base() {
+            PathBase::Build => &self.paths.build,
+            _ => &self.paths.source,
+        };
+
+        match kind {
+            InstallKind::Script => vec![entry(
+                installer
+                    .script_file
+                    .as_ref()
+                    .map(|file| PathBuf::from(paths::resolve(&self.paths.source, file))),
+                None,
+            )],
+            InstallKind::Code => vec![entry(None, None)],
+            InstallKind::ImportedRuntimeArtifacts | InstallKind::RuntimeDependencySet => {
+                vec![entry(None, destination_dir.clone())]
+            }
+            _ => {
+                let mut install_paths: Vec<(PathBuf, PathBuf)> = installer
+                    .paths
+                    .iter()
+                    .map(|path| match path {
+                        InstallPath::PathCombination(combined) => {
+                            // the portion after the last `/` is the name below the destination
+                            let name = combined.rsplit('/').next().unwrap_or(combined);
+                            (PathBuf::from(combined), PathBuf::from(name))
+                        }
+                        InstallPath::FromTo(from_to) => (from_to.from.clone(), from_to.to.clone()),
+                    })
+                    .collect();
+
+                if install_paths.is_empty() && kind == InstallKind::Target {
+                    if let Some(target) = installed_target {
+                        install_paths = target
+                            .artifacts
+                            .iter()
+                            .map(|artifact| {
+                                let name = artifact
+                                    .path
+                                    .file_name()
+                                    .map_or_else(|| artifact.path.clone(), PathBuf::from);
+                                (artifact.path.clone(), name)
+                            })
+                            .collect();
+                    }
+                }
+
+                install_paths
+                    .into_iter()
+                    .map(|(from, to)| {
+                        entry(
+                            Some(PathBuf::from(paths::resolve(base, &from))),
+                            destination_dir
+                                .as_ref()
+                                .map(|dir| PathBuf::from(paths::normalize(&dir.join(&to)))),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::install_manifest::*;
+    use crate::objects::codemodel_v2::{Artifact, Directory, Target};
+    use serde_json::json;
+
+    #[test]
+    fn test_install_manifest() {
+        let installers = json!([
+            {
+                "component" : "Runtime",
+                "destination" : "bin",
+                "paths" : [ "app/app" ],
+                "targetId" : "app::@1",
+                "targetIndex" : 0,
+                "type" : "target"
+            },
+            {
+                "component" : "Development",
+                "destination" : "include",
+                "paths" : [ "include/api.h", { "from" : "include/detail/impl.h", "to" : "detail/impl.h" } ],
+                "type" : "file"
+            },
+            {
+                "component" : "Development",
+                "destination" : "/opt/share/doc",
+                "isExcludeFromAll" : true,
+                "isOptional" : true,
+                "paths" : [ "docs" ],
+                "type" : "directory"
+            },
+            {
+                "component" : "Runtime",
+                "destination" : "lib",
+                "targetId" : "lib::@1",
+                "targetIndex" : 1,
+                "type" : "target"
+            },
+            {
+                "component" : "Unspecified",
+                "isForAllComponents" : true,
+                "scriptFile" : "cmake/post_install.cmake",
+                "type" : "script"
+            }
+        ]);
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/src".into();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Release".into(),
+            targets: vec![
+                Target {
+                    name: "app".into(),
+                    ..Default::default()
+                },
+                Target {
+                    name: "lib".into(),
+                    artifacts: vec![Artifact {
+                        path: "lib/libfoo.so".into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            directories: vec![Directory {
+                installers: serde_json::from_value(installers).unwrap(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+
+        let manifest = codemodel
+            .install_manifest(&InstallOptions::new("/usr/local"))
+            .unwrap();
+        let files: Vec<(String, String)> = manifest
+            .files()
+            .map(|entry| {
+                (
+                    entry.source.as_ref().unwrap().display().to_string(),
+                    entry.destination.as_ref().unwrap().display().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("/build/app/app".into(), "/usr/local/bin/app".into()),
+                (
+                    "/src/include/api.h".into(),
+                    "/usr/local/include/api.h".into()
+                ),
+                (
+                    "/src/include/detail/impl.h".into(),
+                    "/usr/local/include/detail/impl.h".into()
+                ),
+                (
+                    "/build/lib/libfoo.so".into(),
+                    "/usr/local/lib/libfoo.so".into()
+                ),
+            ]
+        );
+        assert_eq!(manifest.entries.len(), 5);
+        assert_eq!(manifest.entries[0].target.as_deref(), Some("app"));
+        assert_eq!(
+            manifest.entries[4].source,
+            Some(PathBuf::from("/src/cmake/post_install.cmake"))
+        );
+
+        let mut options = InstallOptions::new("/usr/local");
+        options.configuration("Release").component("Development");
+        let development = codemodel.install_manifest(&options).unwrap();
+        assert_eq!(development.entries.len(), 4);
+        assert_eq!(development.entries[2].kind, InstallKind::Directory);
+        assert!(development.entries[2].optional);
+        assert_eq!(
+            development.entries[2].destination,
+            Some(PathBuf::from("/opt/share/doc/docs"))
+        );
+        assert_eq!(development.entries[3].kind, InstallKind::Script);
+
+        options.configuration("Debug");
+        assert!(codemodel.install_manifest(&options).is_none());
+    }
+}
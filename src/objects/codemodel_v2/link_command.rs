@@ -0,0 +1,618 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::command_line::{CommandLineStyle, SplitError};
+use super::target::{Archive, CommandFragment, Link, Target};
+use crate::objects::toolchains_v1::{Compiler, FrontendVariant, Toolchains};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Link or archive command fragments split by their role.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LinkFragments {
+    /// Linker or archiver flags (role `flags` and arguments of the search path roles that are no path), excluding rpath entries.
+    pub flags: Vec<String>,
+
+    /// Library search paths (role `libraryPath`), e.g. `-L<path>` or `/LIBPATH:<path>`.
+    pub library_paths: Vec<PathBuf>,
+
+    /// Framework search paths (role `frameworkPath`), e.g. `-F<path>`.
+    pub framework_paths: Vec<PathBuf>,
+
+    /// Libraries in link order (role `libraries`).
+    pub libraries: Vec<LinkLibrary>,
+
+    /// Runtime search paths passed via `-Wl,-rpath,<path>` in any role.
+    pub rpaths: Vec<PathBuf>,
+
+    /// True when link-time optimization is enabled.
+    pub lto: bool,
+}
+
+/// A single entry of the `libraries` role.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum LinkLibrary {
+    /// Path to a library file, e.g. `libfoo/libfoo.a` or `/usr/lib/libz.so`.
+    Path(PathBuf),
+
+    /// Library linked by name, e.g. `-lpthread` or `kernel32.lib`.
+    Name(String),
+
+    /// Apple framework linked via `-framework <name>`.
+    Framework(String),
+
+    /// Any other flag in the libraries role, e.g. `-Wl,--whole-archive` or `-pthread`.
+    Flag(String),
+}
+
+/// Reconstructed command line of the tool that links or archives a target.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LinkCommand {
+    /// Linker driver or archiver executable.
+    pub program: PathBuf,
+
+    /// Arguments passed to the program.
+    /// Object files are not part of the cmake-file-api and therefore missing.
+    pub arguments: Vec<String>,
+
+    /// Artifacts written by the command, see `Target::artifacts`.
+    pub outputs: Vec<PathBuf>,
+}
+
+impl LinkCommand {
+    /// Program followed by its arguments
+    #[must_use]
+    pub fn to_command_line(&self) -> Vec<String> {
+        std::iter::once(self.program.to_string_lossy().into_owned())
+            .chain(self.arguments.iter().cloned())
+            .collect()
+    }
+}
+
+impl LinkFragments {
+    /// Split role-tagged command fragments into typed entries
+    ///
+    /// # Errors
+    ///
+    /// `SplitError::UnbalancedQuotes`: if a fragment can not be split into arguments
+    pub fn parse(
+        fragments: &[CommandFragment],
+        lto: bool,
+        style: CommandLineStyle,
+    ) -> Result<Self, SplitError> {
+        let mut result = LinkFragments {
+            lto,
+            ..Default::default()
+        };
+
+        for fragment in fragments {
+            let args = style.split(&fragment.fragment)?;
+            match fragment.role.as_str() {
+                "libraryPath" => {
+                    let paths = result.push_search_paths(args, &["-L", "/LIBPATH:", "-LIBPATH:"]);
+                    result.library_paths.extend(paths);
+                }
+                "frameworkPath" => {
+                    let paths = result.push_search_paths(args, &["-F", "-iframework"]);
+                    result.framework_paths.extend(paths);
+                }
+                "libraries" => result.push_libraries(args),
+                _ => {
+                    for arg in args {
+                        if !result.push_rpath(&arg) {
+                            result.flags.push(arg);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Libraries linked by name (`-l<name>`, `<name>.lib`)
+    #[must_use]
+    pub fn library_names(&self) -> Vec<&str> {
+        self.libraries
+            .iter()
+            .filter_map(|library| match library {
+                LinkLibrary::Name(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Libraries linked by path
+    #[must_use]
+    pub fn library_files(&self) -> Vec<&Path> {
+        self.libraries
+            .iter()
+            .filter_map(|library| match library {
+                LinkLibrary::Path(path) => Some(path.as_path()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Frameworks linked via `-framework`
+    #[must_use]
+    pub fn frameworks(&self) -> Vec<&str> {
+        self.libraries
+            .iter()
+            .filter_map(|library| match library {
+                LinkLibrary::Framework(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Collect the paths of search path arguments, any other argument is kept as flag
+    fn push_search_paths(&mut self, args: Vec<String>, prefixes: &[&str]) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            match Self::strip_search_prefix(&arg, prefixes) {
+                // separated form, e.g. `-L /usr/lib`
+                Some("") => match iter.next() {
+                    Some(path) => paths.push(PathBuf::from(path)),
+                    None => self.flags.push(arg),
+                },
+                Some(path) => paths.push(PathBuf::from(path)),
+                None => {
+                    if !self.push_rpath(&arg) {
+                        self.flags.push(arg);
+                    }
+                }
+            }
+        }
+        paths
+    }
+
+    /// Strip the longest matching prefix, MSVC style options (`/LIBPATH:`) are matched case-insensitively
+    fn strip_search_prefix<'a>(arg: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+        prefixes
+            .iter()
+            .filter(|prefix| {
+                arg.get(..prefix.len()).map_or(false, |head| {
+                    if prefix.ends_with(':') {
+                        head.eq_ignore_ascii_case(prefix)
+                    } else {
+                        head == **prefix
+                    }
+                })
+            })
+            .max_by_key(|prefix| prefix.len())
+            .map(|prefix| &arg[prefix.len()..])
+    }
+
+    fn push_libraries(&mut self, args: Vec<String>) {
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            if self.push_rpath(&arg) {
+                continue;
+            }
+
+            let library = if arg == "-framework" {
+                match iter.next() {
+                    Some(name) => LinkLibrary::Framework(name),
+                    None => LinkLibrary::Flag(arg),
+                }
+            } else if let Some(name) = arg.strip_prefix("-l") {
+                LinkLibrary::Name(name.to_owned())
+            } else if arg.starts_with('-') {
+                LinkLibrary::Flag(arg)
+            } else if !arg.contains(['/', '\\']) && arg.to_ascii_lowercase().ends_with(".lib") {
+                LinkLibrary::Name(arg[..arg.len() - 4].to_owned())
+            } else {
+                LinkLibrary::Path(PathBuf::from(arg))
+            };
+            self.libraries.push(library);
+        }
+    }
+
+    /// Push rpath entries of `-Wl,-rpath,<paths>` or `-Wl,-rpath=<paths>`, returns false if `arg` is no rpath flag
+    fn push_rpath(&mut self, arg: &str) -> bool {
+        let paths = match arg
+            .strip_prefix("-Wl,-rpath,")
+            .or_else(|| arg.strip_prefix("-Wl,-rpath="))
+        {
+            Some(paths) => paths,
+            None => return false,
+        };
+
+        self.rpaths.extend(
+            paths
+                .split([':', ','])
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        );
+        true
+    }
+}
+
+impl Link {
+    /// Link command fragments split by their role
+    ///
+    /// # Errors
+    ///
+    /// see [`LinkFragments::parse`]
+    pub fn fragments(&self, style: CommandLineStyle) -> Result<LinkFragments, SplitError> {
+        LinkFragments::parse(&self.command_fragments, self.lto, style)
+    }
+}
+
+impl Archive {
+    /// Archive command fragments split by their role
+    ///
+    /// # Errors
+    ///
+    /// see [`LinkFragments::parse`]
+    pub fn fragments(&self, style: CommandLineStyle) -> Result<LinkFragments, SplitError> {
+        LinkFragments::parse(&self.command_fragments, self.lto, style)
+    }
+}
+
+impl Target {
+    /// Reconstruct the link command of an executable, shared or module library
+    ///
+    /// The linker driver is the compiler of the link language from the toolchains.
+    /// Returns `None` if the target is not linked or no compiler path is known for the link language.
+    ///
+    /// # Errors
+    ///
+    /// `SplitError::UnbalancedQuotes`: if a fragment can not be split into arguments
+    pub fn link_command(&self, toolchains: &Toolchains) -> Result<Option<LinkCommand>, SplitError> {
+        let (link, compiler) = match self.link.as_ref().and_then(|link| {
+            toolchains
+                .toolchain(&link.language)
+                .map(|toolchain| (link, &toolchain.compiler))
+        }) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let program = match compiler.path.clone() {
+            Some(program) => program,
+            None => return Ok(None),
+        };
+        let msvc = compiler.frontend_variant() == Some(FrontendVariant::Msvc);
+        let style = CommandLineStyle::for_compiler(compiler);
+
+        let mut flags: Vec<String> = Vec::new();
+        let mut libraries: Vec<String> = Vec::new();
+        for fragment in &link.command_fragments {
+            let args = style.split(&fragment.fragment)?;
+            if fragment.role == "flags" {
+                flags.extend(args);
+            } else {
+                libraries.extend(args);
+            }
+        }
+
+        let outputs = self.outputs();
+        let primary = outputs.first().map(|path| path.display().to_string());
+
+        let mut arguments: Vec<String> = Vec::new();
+        if msvc {
+            // cl-style drivers pass everything after `/link` to the linker
+            arguments.extend(primary.map(|output| format!("/Fe{output}")));
+            arguments.push("/link".to_owned());
+            arguments.extend(flags);
+        } else {
+            arguments.extend(flags);
+            if let Some(sysroot) = &link.sysroot {
+                arguments.push(format!("--sysroot={}", sysroot.path.display()));
+            }
+            if let Some(output) = primary {
+                arguments.push("-o".to_owned());
+                arguments.push(output);
+            }
+        }
+        arguments.extend(libraries);
+
+        Ok(Some(LinkCommand {
+            program,
+            arguments,
+            outputs,
+        }))
+    }
+
+    /// Reconstruct the archive command of a static library
+    ///
+    /// The archiver is not part of the toolchains object (see `CMAKE_AR` in the cache),
+    /// so it has to be passed in. The command line syntax (`ar` or `lib.exe`) is derived from the
+    /// compiler of the first compile group.
+    /// Returns `None` if the target is not archived.
+    ///
+    /// # Errors
+    ///
+    /// `SplitError::UnbalancedQuotes`: if a fragment can not be split into arguments
+    pub fn archive_command<P: AsRef<Path>>(
+        &self,
+        toolchains: &Toolchains,
+        archiver: P,
+    ) -> Result<Option<LinkCommand>, SplitError> {
+        let archive = match self.archive.as_ref() {
+            Some(archive) => archive,
+            None => return Ok(None),
+        };
+        let compiler = self
+            .compile_groups
+            .first()
+            .and_then(|group| toolchains.toolchain(&group.language))
+            .map(|toolchain| &toolchain.compiler);
+        let msvc = compiler.and_then(Compiler::frontend_variant) == Some(FrontendVariant::Msvc);
+        let style = compiler.map_or(CommandLineStyle::Posix, CommandLineStyle::for_compiler);
+
+        let mut flags: Vec<String> = Vec::new();
+        for fragment in &archive.command_fragments {
+            flags.extend(style.split(&fragment.fragment)?);
+        }
+
+        let outputs = self.outputs();
+        let primary = outputs
+            .first()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+
+        let arguments = if msvc {
+            flags
+                .into_iter()
+                .chain(std::iter::once(format!("/OUT:{primary}")))
+                .collect()
+        } else {
+            ["qc".to_owned(), primary]
+                .into_iter()
+                .chain(flags)
+                .collect()
+        };
+
+        Ok(Some(LinkCommand {
+            program: archiver.as_ref().to_path_buf(),
+            arguments,
+            outputs,
+        }))
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        self.artifacts
+            .iter()
+            .map(|artifact| artifact.path.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::link_command::*;
+    use crate::objects::codemodel_v2::Artifact;
+    use crate::objects::toolchains_v1::{Compiler, Toolchain};
+    use serde_json::json;
+
+    #[test]
+    fn test_link_fragments() {
+        let json = json!({
+            "commandFragments" :
+            [
+                {
+                    "fragment" : "-O3 -DNDEBUG -Wl,-rpath,/opt/foo/lib:/opt/bar/lib",
+                    "role" : "flags"
+                },
+                {
+                    "fragment" : "-L/opt/foo/lib",
+                    "role" : "libraryPath"
+                },
+                {
+                    "fragment" : "-F/Library/Frameworks",
+                    "role" : "frameworkPath"
+                },
+                {
+                    "fragment" : "libfoo/libfoo.a",
+                    "role" : "libraries"
+                },
+                {
+                    "fragment" : "-lpthread",
+                    "role" : "libraries"
+                },
+                {
+                    "fragment" : "-framework CoreFoundation",
+                    "role" : "libraries"
+                },
+                {
+                    "fragment" : "-Wl,--as-needed",
+                    "role" : "libraries"
+                },
+                {
+                    "fragment" : "kernel32.lib",
+                    "role" : "libraries"
+                }
+            ],
+            "language" : "CXX",
+            "lto" : true
+        });
+
+        let link = serde_json::from_value::<Link>(json).unwrap();
+        let fragments = link.fragments(CommandLineStyle::Posix).unwrap();
+        assert_eq!(fragments.flags, vec!["-O3", "-DNDEBUG"]);
+        assert_eq!(
+            fragments.rpaths,
+            vec![PathBuf::from("/opt/foo/lib"), PathBuf::from("/opt/bar/lib")]
+        );
+        assert_eq!(fragments.library_paths, vec![PathBuf::from("/opt/foo/lib")]);
+        assert_eq!(
+            fragments.framework_paths,
+            vec![PathBuf::from("/Library/Frameworks")]
+        );
+        assert_eq!(
+            fragments.libraries,
+            vec![
+                LinkLibrary::Path("libfoo/libfoo.a".into()),
+                LinkLibrary::Name("pthread".into()),
+                LinkLibrary::Framework("CoreFoundation".into()),
+                LinkLibrary::Flag("-Wl,--as-needed".into()),
+                LinkLibrary::Name("kernel32".into()),
+            ]
+        );
+        assert!(fragments.lto);
+
+        let toolchains = Toolchains {
+            toolchains: vec![Toolchain {
+                language: "CXX".into(),
+                compiler: Compiler {
+                    path: Some("/usr/bin/c++".into()),
+                    id: Some("GNU".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let target = Target {
+            link: Some(link),
+            artifacts: vec![Artifact {
+                path: "app".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let command = target.link_command(&toolchains).unwrap().unwrap();
+        assert_eq!(command.outputs, vec![PathBuf::from("app")]);
+        assert_eq!(
+            command.to_command_line(),
+            vec![
+                "/usr/bin/c++",
+                "-O3",
+                "-DNDEBUG",
+                "-Wl,-rpath,/opt/foo/lib:/opt/bar/lib",
+                "-o",
+                "app",
+                "-L/opt/foo/lib",
+                "-F/Library/Frameworks",
+                "libfoo/libfoo.a",
+                "-lpthread",
+                "-framework",
+                "CoreFoundation",
+                "-Wl,--as-needed",
+                "kernel32.lib",
+            ]
+        );
+
+        let library = Target {
+            archive: Some(Archive {
+                command_fragments: vec![CommandFragment {
+                    fragment: "-T".into(),
+                    role: "flags".into(),
+                    ..Default::default()
+                }],
+                lto: false,
+                ..Default::default()
+            }),
+            artifacts: vec![Artifact {
+                path: "libfoo/libfoo.a".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            library
+                .archive_command(&toolchains, "/usr/bin/ar")
+                .unwrap()
+                .unwrap()
+                .to_command_line(),
+            vec!["/usr/bin/ar", "qc", "libfoo/libfoo.a", "-T"]
+        );
+    }
+
+    #[test]
+    fn test_msvc_link_fragments() {
+        let fragment = |fragment: &str, role: &str| CommandFragment {
+            fragment: fragment.into(),
+            role: role.into(),
+            ..Default::default()
+        };
+        let fragments = vec![
+            fragment("/machine:x64 /INCREMENTAL:NO", "flags"),
+            fragment(
+                r#"/LIBPATH:C:\deps\lib "/LIBPATH:C:\Program Files\sdk" /nologo"#,
+                "libraryPath",
+            ),
+            fragment(
+                r#"C:\lib\x.lib "C:\Program Files\sdk\y.lib" kernel32.lib"#,
+                "libraries",
+            ),
+        ];
+
+        let parsed = LinkFragments::parse(&fragments, false, CommandLineStyle::Windows).unwrap();
+        assert_eq!(
+            parsed.flags,
+            vec!["/machine:x64", "/INCREMENTAL:NO", "/nologo"]
+        );
+        assert_eq!(
+            parsed.library_paths,
+            vec![
+                PathBuf::from(r"C:\deps\lib"),
+                PathBuf::from(r"C:\Program Files\sdk")
+            ]
+        );
+        assert_eq!(
+            parsed.libraries,
+            vec![
+                LinkLibrary::Path(r"C:\lib\x.lib".into()),
+                LinkLibrary::Path(r"C:\Program Files\sdk\y.lib".into()),
+                LinkLibrary::Name("kernel32".into()),
+            ]
+        );
+
+        let toolchains = Toolchains {
+            toolchains: vec![Toolchain {
+                language: "CXX".into(),
+                compiler: Compiler {
+                    path: Some("C:/VS/bin/cl.exe".into()),
+                    id: Some("MSVC".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let target = Target {
+            link: Some(Link {
+                command_fragments: fragments,
+                language: "CXX".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let command = target.link_command(&toolchains).unwrap().unwrap();
+        assert!(command
+            .arguments
+            .iter()
+            .any(|arg| arg == r"C:\Program Files\sdk\y.lib"));
+
+        let dash_style = [fragment(
+            r#"-LIBPATH:C:\deps\lib /libpath:C:\sdk\lib -L C:\other\lib"#,
+            "libraryPath",
+        )];
+        let dash_parsed =
+            LinkFragments::parse(&dash_style, false, CommandLineStyle::Windows).unwrap();
+        assert_eq!(
+            dash_parsed.library_paths,
+            vec![
+                PathBuf::from(r"C:\deps\lib"),
+                PathBuf::from(r"C:\sdk\lib"),
+                PathBuf::from(r"C:\other\lib")
+            ]
+        );
+        assert!(dash_parsed.flags.is_empty());
+
+        let unbalanced = [fragment(r#""C:\lib\x.lib"#, "libraries")];
+        assert!(matches!(
+            LinkFragments::parse(&unbalanced, false, CommandLineStyle::Windows),
+            Err(SplitError::UnbalancedQuotes(_))
+        ));
+    }
+}
@@ -0,0 +1,364 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::{Configuration, DirectoryReference, Project, TargetReference};
+use super::index::{DirectoryIdx, ProjectIdx, TargetIdx};
+
+/// Errors for navigating the project and directory hierarchy
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum NavigationError {
+    #[error("project index {0} is out of range")]
+    ProjectIndex(ProjectIdx),
+
+    #[error("directory index {0} is out of range")]
+    DirectoryIndex(DirectoryIdx),
+
+    #[error("target index {0} is out of range")]
+    TargetIndex(TargetIdx),
+
+    #[error("project `{0}` not found")]
+    ProjectNotFound(String),
+
+    #[error("target `{0}` not found")]
+    TargetNotFound(String),
+
+    #[error("configuration has no projects")]
+    NoProjects,
+
+    #[error("parent indices form a cycle")]
+    Cycle,
+}
+
+impl Configuration {
+    /// Project by index, `None` if the index is out of range
+    #[must_use]
+    pub fn project(&self, index: ProjectIdx) -> Option<&Project> {
+        self.projects.get(index.get())
+    }
+
+    /// Directory reference by index, `None` if the index is out of range
+    #[must_use]
+    pub fn directory_ref(&self, index: DirectoryIdx) -> Option<&DirectoryReference> {
+        self.directory_refs.get(index.get())
+    }
+
+    /// Target reference by index, `None` if the index is out of range
+    #[must_use]
+    pub fn target_ref(&self, index: TargetIdx) -> Option<&TargetReference> {
+        self.target_refs.get(index.get())
+    }
+
+    /// Top-level project, the first entry of the projects
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::NoProjects`: if the configuration has no projects
+    pub fn root_project(&self) -> Result<&Project, NavigationError> {
+        self.projects.first().ok_or(NavigationError::NoProjects)
+    }
+
+    /// Project and its index by name
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::ProjectNotFound`: if no project has the name
+    pub fn project_by_name(&self, name: &str) -> Result<(ProjectIdx, &Project), NavigationError> {
+        self.projects
+            .iter()
+            .enumerate()
+            .find(|(_, project)| project.name == name)
+            .map(|(index, project)| (ProjectIdx::new(index), project))
+            .ok_or_else(|| NavigationError::ProjectNotFound(name.to_owned()))
+    }
+
+    /// Direct subprojects of a project
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::ProjectIndex`: if an index is out of range
+    pub fn child_projects(
+        &self,
+        index: ProjectIdx,
+    ) -> Result<impl Iterator<Item = &Project> + '_, NavigationError> {
+        let children = &self
+            .project(index)
+            .ok_or(NavigationError::ProjectIndex(index))?
+            .child_indexes;
+        for &child in children {
+            self.project(child)
+                .ok_or(NavigationError::ProjectIndex(child))?;
+        }
+        Ok(children
+            .iter()
+            .filter_map(|child| self.projects.get(child.get())))
+    }
+
+    /// Parent projects of a project, starting with the direct parent
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::ProjectIndex`: if an index is out of range
+    /// `NavigationError::Cycle`: if the parent indices form a cycle
+    pub fn project_ancestors(
+        &self,
+        index: ProjectIdx,
+    ) -> Result<impl Iterator<Item = &Project> + '_, NavigationError> {
+        ancestors(
+            &self.projects,
+            self.project(index)
+                .ok_or(NavigationError::ProjectIndex(index))?
+                .parent_index,
+            |project| project.parent_index,
+            NavigationError::ProjectIndex,
+        )
+    }
+
+    /// Direct subdirectories of a directory
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::DirectoryIndex`: if an index is out of range
+    pub fn child_directories(
+        &self,
+        index: DirectoryIdx,
+    ) -> Result<impl Iterator<Item = &DirectoryReference> + '_, NavigationError> {
+        let children = &self
+            .directory_ref(index)
+            .ok_or(NavigationError::DirectoryIndex(index))?
+            .child_indexes;
+        for &child in children {
+            self.directory_ref(child)
+                .ok_or(NavigationError::DirectoryIndex(child))?;
+        }
+        Ok(children
+            .iter()
+            .filter_map(|child| self.directory_refs.get(child.get())))
+    }
+
+    /// Parent directories of a directory, starting with the direct parent
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::DirectoryIndex`: if an index is out of range
+    /// `NavigationError::Cycle`: if the parent indices form a cycle
+    pub fn directory_ancestors(
+        &self,
+        index: DirectoryIdx,
+    ) -> Result<impl Iterator<Item = &DirectoryReference> + '_, NavigationError> {
+        ancestors(
+            &self.directory_refs,
+            self.directory_ref(index)
+                .ok_or(NavigationError::DirectoryIndex(index))?
+                .parent_index,
+            |directory| directory.parent_index,
+            NavigationError::DirectoryIndex,
+        )
+    }
+
+    /// Targets of a project including the targets of all its subprojects
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::ProjectNotFound`: if no project has the name
+    /// `NavigationError::ProjectIndex`, `NavigationError::TargetIndex`: if an index is out of range
+    /// `NavigationError::Cycle`: if the child indices form a cycle
+    pub fn project_targets(
+        &self,
+        name: &str,
+    ) -> Result<impl Iterator<Item = &TargetReference> + '_, NavigationError> {
+        let (root, _) = self.project_by_name(name)?;
+
+        let mut visited = Vec::new();
+        let mut pending = vec![root];
+        while let Some(index) = pending.pop() {
+            if visited.contains(&index) {
+                return Err(NavigationError::Cycle);
+            }
+            visited.push(index);
+
+            let project = self
+                .project(index)
+                .ok_or(NavigationError::ProjectIndex(index))?;
+            for &target in &project.target_indexes {
+                self.target_ref(target)
+                    .ok_or(NavigationError::TargetIndex(target))?;
+            }
+            // keep the order of the children when popping
+            pending.extend(project.child_indexes.iter().rev());
+        }
+
+        Ok(visited
+            .into_iter()
+            .filter_map(|index| self.projects.get(index.get()))
+            .flat_map(|project| &project.target_indexes)
+            .filter_map(|target| self.target_refs.get(target.get())))
+    }
+
+    /// Directory whose CMakeLists.txt defines a target
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::TargetNotFound`: if no target has the name
+    /// `NavigationError::DirectoryIndex`: if the directory index of the target is out of range
+    pub fn defining_directory(
+        &self,
+        target_name: &str,
+    ) -> Result<&DirectoryReference, NavigationError> {
+        let target = self
+            .target_refs
+            .iter()
+            .find(|target| target.name == target_name)
+            .ok_or_else(|| NavigationError::TargetNotFound(target_name.to_owned()))?;
+        self.directory_ref(target.directory_index)
+            .ok_or(NavigationError::DirectoryIndex(target.directory_index))
+    }
+
+    /// Minimum required `CMake` version in effect for a directory
+    ///
+    /// `CMake` reports the version of the most local cmake_minimum_required() call, if it is missing
+    /// for the directory the nearest parent directory with a version is used.
+    ///
+    /// # Errors
+    ///
+    /// `NavigationError::DirectoryIndex`: if an index is out of range
+    /// `NavigationError::Cycle`: if the parent indices form a cycle
+    pub fn minimum_cmake_version(
+        &self,
+        index: DirectoryIdx,
+    ) -> Result<Option<&str>, NavigationError> {
+        let directory = self
+            .directory_ref(index)
+            .ok_or(NavigationError::DirectoryIndex(index))?;
+        let version = std::iter::once(directory)
+            .chain(self.directory_ancestors(index)?)
+            .find_map(|dir| dir.minimum_cmake_version.as_ref())
+            .map(|version| version.version.as_str());
+        Ok(version)
+    }
+}
+
+/// Entries reached by following the parent indices, the whole chain is validated before iterating
+fn ancestors<'a, T, I>(
+    entries: &'a [T],
+    first: Option<I>,
+    parent: fn(&T) -> Option<I>,
+    out_of_range: fn(I) -> NavigationError,
+) -> Result<impl Iterator<Item = &'a T> + 'a, NavigationError>
+where
+    I: Copy + Into<usize> + 'a,
+{
+    let mut steps = 0;
+    let mut current = first;
+    while let Some(index) = current {
+        if steps >= entries.len() {
+            return Err(NavigationError::Cycle);
+        }
+        current = parent(
+            entries
+                .get(index.into())
+                .ok_or_else(|| out_of_range(index))?,
+        );
+        steps += 1;
+    }
+
+    let first_entry = first.and_then(|index| entries.get(index.into()));
+    Ok(std::iter::successors(first_entry, move |entry| {
+        parent(entry).and_then(|index| entries.get(index.into()))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::navigation::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_navigation() {
+        let config: Configuration = serde_json::from_value(json!({
+            "name": "Debug",
+            "projects": [
+                { "name": "root", "childIndexes": [1], "directoryIndexes": [0], "targetIndexes": [0] },
+                { "name": "sub", "parentIndex": 0, "childIndexes": [2], "directoryIndexes": [1], "targetIndexes": [1] },
+                { "name": "leaf", "parentIndex": 1, "directoryIndexes": [2], "targetIndexes": [2] }
+            ],
+            "directories": [
+                { "source": ".", "build": ".", "childIndexes": [1], "projectIndex": 0,
+                  "minimumCMakeVersion": { "string": "3.20" }, "jsonFile": "directory-.json" },
+                { "source": "sub", "build": "sub", "parentIndex": 0, "childIndexes": [2], "projectIndex": 1,
+                  "jsonFile": "directory-sub.json" },
+                { "source": "sub/leaf", "build": "sub/leaf", "parentIndex": 1, "projectIndex": 2,
+                  "minimumCMakeVersion": { "string": "3.28" }, "jsonFile": "directory-leaf.json" }
+            ],
+            "targets": [
+                { "name": "app", "id": "app::@1", "directoryIndex": 0, "projectIndex": 0, "jsonFile": "app.json" },
+                { "name": "lib", "id": "lib::@2", "directoryIndex": 1, "projectIndex": 1, "jsonFile": "lib.json" },
+                { "name": "leaf", "id": "leaf::@3", "directoryIndex": 2, "projectIndex": 2, "jsonFile": "leaf.json" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(config.root_project().unwrap().name, "root");
+        assert_eq!(
+            config
+                .child_projects(ProjectIdx::new(0))
+                .unwrap()
+                .next()
+                .unwrap()
+                .name,
+            "sub"
+        );
+        let ancestors: Vec<&str> = config
+            .project_ancestors(ProjectIdx::new(2))
+            .unwrap()
+            .map(|project| project.name.as_str())
+            .collect();
+        assert_eq!(ancestors, vec!["sub", "root"]);
+        assert_eq!(
+            config
+                .directory_ancestors(DirectoryIdx::new(2))
+                .unwrap()
+                .count(),
+            2
+        );
+        assert_eq!(
+            config
+                .child_directories(DirectoryIdx::new(1))
+                .unwrap()
+                .next()
+                .unwrap()
+                .source
+                .to_str(),
+            Some("sub/leaf")
+        );
+
+        let targets: Vec<&str> = config
+            .project_targets("sub")
+            .unwrap()
+            .map(|target| target.name.as_str())
+            .collect();
+        assert_eq!(targets, vec!["lib", "leaf"]);
+        assert_eq!(config.project_targets("root").unwrap().count(), 3);
+        assert_eq!(
+            config.project_targets("missing").err(),
+            Some(NavigationError::ProjectNotFound("missing".into()))
+        );
+        assert_eq!(config.project(ProjectIdx::new(5)), None);
+        assert_eq!(
+            config.child_projects(ProjectIdx::new(5)).err(),
+            Some(NavigationError::ProjectIndex(ProjectIdx::new(5)))
+        );
+
+        assert_eq!(
+            config.defining_directory("lib").unwrap().source.to_str(),
+            Some("sub")
+        );
+        assert_eq!(
+            config.minimum_cmake_version(DirectoryIdx::new(1)).unwrap(),
+            Some("3.20")
+        );
+        assert_eq!(
+            config.minimum_cmake_version(DirectoryIdx::new(2)).unwrap(),
+            Some("3.28")
+        );
+    }
+}
@@ -0,0 +1,404 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::{CodeModel, Configuration};
+use super::index::{DirectoryIdx, InstallerIdx};
+use super::install_manifest::{InstallEntry, InstallKind, InstallOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Location of an installer in the directories of a configuration.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InstallerLocation {
+    /// 0-based index into the directories of the configuration.
+    pub directory_index: DirectoryIdx,
+
+    /// 0-based index into the installers of the directory.
+    pub installer_index: InstallerIdx,
+}
+
+/// An install component (e.g. for `CPack`) with everything it installs.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InstallComponent {
+    /// Name of the component.
+    pub name: String,
+
+    /// Installers of the component.
+    pub installers: Vec<InstallerLocation>,
+
+    /// Installed entries of the component, see [`CodeModel::install_manifest`].
+    pub entries: Vec<InstallEntry>,
+}
+
+/// Targets exported by install(EXPORT).
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ExportSet {
+    /// Name of the export.
+    pub name: String,
+
+    /// Names of the exported targets.
+    pub targets: Vec<String>,
+
+    /// Ids of the exported targets.
+    pub target_ids: Vec<String>,
+
+    /// Install destination of the export files.
+    pub destination: Option<String>,
+
+    /// Component of the install(EXPORT) rule.
+    pub component: String,
+
+    /// Export files generated by `CMake`, relative to the top-level build directory.
+    pub files: Vec<PathBuf>,
+}
+
+impl ExportSet {
+    /// Namespace of the exported targets, e.g. `Foo::`
+    ///
+    /// The namespace is not part of the cmake-file-api, it is read from the export files generated by `CMake`
+    /// (`add_library(<namespace><target> ...)`). Imported names are matched against the longest exported
+    /// target name first and a namespace that yields an imported `<namespace><target>` for every exported
+    /// target is preferred, so a target name that is a suffix of another one (`core` and `libcore`)
+    /// does not leak into the namespace.
+    /// Returns `None` if the files can not be read or the exported targets are not found in them.
+    pub fn read_namespace<P: AsRef<Path>>(&self, build_dir: P) -> Option<String> {
+        let mut targets: Vec<&str> = self.targets.iter().map(String::as_str).collect();
+        targets.sort_by_key(|target| std::cmp::Reverse(target.len()));
+
+        self.files.iter().find_map(|file| {
+            let content = fs::read_to_string(build_dir.as_ref().join(file)).ok()?;
+            let imported: Vec<&str> = content
+                .lines()
+                .filter_map(|line| {
+                    line.trim()
+                        .strip_prefix("add_library(")
+                        .or_else(|| line.trim().strip_prefix("add_executable("))?
+                        .split_whitespace()
+                        .next()
+                })
+                .collect();
+
+            // candidates in file order, longest target name first
+            let namespaces: Vec<&str> = imported
+                .iter()
+                .flat_map(|name| {
+                    targets
+                        .iter()
+                        .filter_map(move |target| name.strip_suffix(target))
+                })
+                .collect();
+            namespaces
+                .iter()
+                .find(|namespace| {
+                    targets
+                        .iter()
+                        .all(|target| imported.contains(&format!("{namespace}{target}").as_str()))
+                })
+                .or_else(|| namespaces.first())
+                .map(|namespace| (*namespace).to_owned())
+        })
+    }
+}
+
+/// Kind of a likely packaging bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum PackagingIssueKind {
+    /// The library target has an install(TARGETS) rule but is not part of any export set.
+    InstalledNotExported,
+
+    /// The target is part of an export set but has no install(TARGETS) rule.
+    ExportedNotInstalled,
+}
+
+/// A likely packaging bug.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackagingIssue {
+    pub kind: PackagingIssueKind,
+
+    /// Name of the target.
+    pub target: String,
+
+    /// Id of the target.
+    pub id: String,
+
+    /// Type of the target, e.g. `SHARED_LIBRARY`.
+    pub type_name: String,
+}
+
+impl Configuration {
+    /// Export sets of all install(EXPORT) rules
+    #[must_use]
+    pub fn export_sets(&self) -> Vec<ExportSet> {
+        self.directories
+            .iter()
+            .flat_map(|directory| &directory.installers)
+            .filter(|installer| installer.installer_type == "export")
+            .map(|installer| ExportSet {
+                name: installer.export_name.clone().unwrap_or_default(),
+                targets: installer
+                    .export_targets
+                    .iter()
+                    .map(|target| {
+                        self.target(target.index)
+                            .map_or_else(|| target.id.clone(), |t| t.name.clone())
+                    })
+                    .collect(),
+                target_ids: installer
+                    .export_targets
+                    .iter()
+                    .map(|target| target.id.clone())
+                    .collect(),
+                destination: installer.destination.clone(),
+                component: installer.component.clone(),
+                files: installer
+                    .paths
+                    .iter()
+                    .map(|install_path| match install_path {
+                        super::InstallPath::PathCombination(path) => PathBuf::from(path),
+                        super::InstallPath::FromTo(from_to) => from_to.from.clone(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Library targets that are installed but not exported, or targets that are exported but not installed
+    ///
+    /// Installed executables are rarely exported and therefore not reported.
+    #[must_use]
+    pub fn packaging_issues(&self) -> Vec<PackagingIssue> {
+        let installers = || {
+            self.directories
+                .iter()
+                .flat_map(|directory| &directory.installers)
+        };
+
+        let installed: HashSet<&str> = installers()
+            .filter(|installer| installer.installer_type == "target")
+            .filter_map(|installer| installer.target_id.as_deref())
+            .collect();
+        let exported: HashSet<&str> = installers()
+            .flat_map(|installer| &installer.export_targets)
+            .map(|target| target.id.as_str())
+            .collect();
+
+        let mut issues = Vec::new();
+        for target in &self.targets {
+            let kind = match (
+                installed.contains(target.id.as_str()),
+                exported.contains(target.id.as_str()),
+            ) {
+                (true, false) if target.type_name.ends_with("_LIBRARY") => {
+                    PackagingIssueKind::InstalledNotExported
+                }
+                (false, true) => PackagingIssueKind::ExportedNotInstalled,
+                _ => continue,
+            };
+            issues.push(PackagingIssue {
+                kind,
+                target: target.name.clone(),
+                id: target.id.clone(),
+                type_name: target.type_name.clone(),
+            });
+        }
+        issues
+    }
+}
+
+impl CodeModel {
+    /// Install components with their installers and installed entries
+    ///
+    /// Components are listed in the order of their first install() rule.
+    /// The components of the options are ignored, every installer is considered.
+    /// Returns `None` if the configuration does not exist.
+    #[must_use]
+    pub fn install_components(&self, options: &InstallOptions) -> Option<Vec<InstallComponent>> {
+        let config = options.select_configuration(self)?;
+
+        let mut components: Vec<InstallComponent> = Vec::new();
+        for (directory_index, directory) in config.directories.iter().enumerate() {
+            for (installer_index, installer) in directory.installers.iter().enumerate() {
+                let position = components
+                    .iter()
+                    .position(|component| component.name == installer.component)
+                    .unwrap_or_else(|| {
+                        components.push(InstallComponent {
+                            name: installer.component.clone(),
+                            ..Default::default()
+                        });
+                        components.len() - 1
+                    });
+
+                let component = &mut components[position];
+                component.installers.push(InstallerLocation {
+                    directory_index: DirectoryIdx::new(directory_index),
+                    installer_index: InstallerIdx::new(installer_index),
+                });
+                component.entries.extend(self.expand_installer(
+                    config,
+                    installer,
+                    options.prefix(),
+                ));
+            }
+        }
+
+        Some(components)
+    }
+}
+
+impl InstallComponent {
+    /// Installed entries that are files or directories
+    pub fn files(&self) -> impl Iterator<Item = &InstallEntry> {
+        self.entries.iter().filter(|entry| {
+            entry.source.is_some() && !matches!(entry.kind, InstallKind::Script | InstallKind::Code)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::packaging::*;
+    use crate::objects::codemodel_v2::Directory;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_packaging() {
+        let installers = json!([
+            {
+                "component" : "Runtime",
+                "destination" : "lib",
+                "paths" : [ "libcore.so" ],
+                "targetId" : "core::@1",
+                "targetIndex" : 0,
+                "type" : "target"
+            },
+            {
+                "component" : "Runtime",
+                "destination" : "bin",
+                "paths" : [ "tool" ],
+                "targetId" : "tool::@1",
+                "targetIndex" : 2,
+                "type" : "target"
+            },
+            {
+                "component" : "Runtime",
+                "destination" : "lib/plugins",
+                "paths" : [ "plugin.so" ],
+                "targetId" : "plugin::@1",
+                "targetIndex" : 3,
+                "type" : "target"
+            },
+            {
+                "component" : "Development",
+                "destination" : "lib/cmake/Foo",
+                "exportName" : "FooTargets",
+                "exportTargets" : [
+                    { "id" : "core::@1", "index" : 0 },
+                    { "id" : "util::@1", "index" : 1 }
+                ],
+                "paths" : [
+                    {
+                        "from" : "CMakeFiles/Export/abc/FooTargets.cmake",
+                        "to" : "FooTargets.cmake"
+                    }
+                ],
+                "type" : "export"
+            }
+        ]);
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Release".into(),
+            targets: vec![
+                target(json!({ "name": "core", "type": "SHARED_LIBRARY" })),
+                target(json!({ "name": "util", "type": "STATIC_LIBRARY" })),
+                target(json!({ "name": "tool" })),
+                target(json!({ "name": "plugin", "type": "MODULE_LIBRARY" })),
+            ],
+            directories: vec![Directory {
+                installers: serde_json::from_value(installers).unwrap(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let config = &codemodel.configurations[0];
+
+        let export_sets = config.export_sets();
+        assert_eq!(export_sets.len(), 1);
+        assert_eq!(export_sets[0].name, "FooTargets");
+        assert_eq!(export_sets[0].targets, vec!["core", "util"]);
+        assert_eq!(
+            export_sets[0].files,
+            vec![PathBuf::from("CMakeFiles/Export/abc/FooTargets.cmake")]
+        );
+
+        let tmp_dir = tempdir::TempDir::new("test_packaging").unwrap();
+        let export_dir = tmp_dir.path().join("CMakeFiles/Export/abc");
+        fs::create_dir_all(&export_dir).unwrap();
+        fs::write(
+            export_dir.join("FooTargets.cmake"),
+            "# Create imported target Foo::core\nadd_library(Foo::core SHARED IMPORTED)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            export_sets[0].read_namespace(tmp_dir.path()).as_deref(),
+            Some("Foo::")
+        );
+
+        let issues = config.packaging_issues();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, PackagingIssueKind::ExportedNotInstalled);
+        assert_eq!(issues[0].target, "util");
+        assert_eq!(issues[1].kind, PackagingIssueKind::InstalledNotExported);
+        assert_eq!(issues[1].target, "plugin");
+
+        let components = codemodel
+            .install_components(&InstallOptions::new("/usr"))
+            .unwrap();
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Runtime", "Development"]);
+        assert_eq!(components[0].installers.len(), 3);
+        assert_eq!(
+            components[0].installers[2].installer_index,
+            InstallerIdx::new(2)
+        );
+        assert_eq!(components[0].files().count(), 3);
+        assert_eq!(
+            components[1].entries[0].destination,
+            Some(PathBuf::from("/usr/lib/cmake/Foo/FooTargets.cmake"))
+        );
+    }
+
+    #[test]
+    fn test_read_namespace() {
+        let export_set = ExportSet {
+            targets: vec!["core".into(), "libcore".into()],
+            files: vec!["FooTargets.cmake".into()],
+            ..Default::default()
+        };
+
+        let tmp_dir = tempdir::TempDir::new("test_read_namespace").unwrap();
+        fs::write(
+            tmp_dir.path().join("FooTargets.cmake"),
+            "add_library(Foo::libcore STATIC IMPORTED)\nadd_library(Foo::core STATIC IMPORTED)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            export_set.read_namespace(tmp_dir.path()).as_deref(),
+            Some("Foo::")
+        );
+    }
+}
@@ -0,0 +1,273 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::CodeModel;
+use super::index::{CompileGroupIdx, SourceIdx, TargetIdx};
+use super::target::{CompileGroup, Source, Target};
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A single use of a source file by a target.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourceUsage {
+    /// Name of the configuration, e.g. Debug.
+    pub configuration: String,
+
+    /// 0-based index into the configurations of the codemodel.
+    pub configuration_index: usize,
+
+    /// Name of the target using the source.
+    pub target: String,
+
+    /// 0-based index into the targets of the configuration.
+    pub target_index: TargetIdx,
+
+    /// 0-based index into the sources of the target.
+    pub source_index: SourceIdx,
+
+    /// 0-based index into the compile groups of the target, present when the source is compiled.
+    pub compile_group_index: Option<CompileGroupIdx>,
+}
+
+impl SourceUsage {
+    /// Resolve the target of the usage
+    #[must_use]
+    pub fn target<'a>(&self, codemodel: &'a CodeModel) -> Option<&'a Target> {
+        codemodel
+            .configurations
+            .get(self.configuration_index)?
+            .target(self.target_index)
+    }
+
+    /// Resolve the source of the usage
+    #[must_use]
+    pub fn source<'a>(&self, codemodel: &'a CodeModel) -> Option<&'a Source> {
+        self.target(codemodel)?.source(self.source_index)
+    }
+
+    /// Resolve the compile group of the usage
+    #[must_use]
+    pub fn compile_group<'a>(&self, codemodel: &'a CodeModel) -> Option<&'a CompileGroup> {
+        self.target(codemodel)?
+            .compile_group(self.compile_group_index?)
+    }
+}
+
+/// An indexed source file with all its usages.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IndexedSource {
+    /// Absolute, normalized path with forward slashes.
+    pub path: PathBuf,
+
+    /// Targets and compile groups using the source, for all configurations.
+    pub usages: Vec<SourceUsage>,
+}
+
+/// Reverse index from source files to the targets and compile groups using them.
+///
+/// Relative source paths are resolved against the top-level source directory and all paths
+/// are normalized (forward slashes, no `.`/`..` components). Lookups are case-insensitive
+/// if the index was created case-insensitive, which is the default on Windows and macOS.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SourceIndex {
+    source_dir: PathBuf,
+    case_sensitive: bool,
+    entries: BTreeMap<String, IndexedSource>,
+}
+
+impl SourceIndex {
+    /// Build the index with the case sensitivity of the host platform
+    #[must_use]
+    pub fn new(codemodel: &CodeModel) -> Self {
+        Self::with_case_sensitivity(codemodel, paths::host_case_sensitive())
+    }
+
+    /// Build the index with explicit case sensitivity
+    #[must_use]
+    pub fn with_case_sensitivity(codemodel: &CodeModel, case_sensitive: bool) -> Self {
+        let mut index = SourceIndex {
+            source_dir: codemodel.paths.source.clone(),
+            case_sensitive,
+            entries: BTreeMap::new(),
+        };
+
+        for (configuration_index, config) in codemodel.configurations.iter().enumerate() {
+            for (target_index, target) in config.targets.iter().enumerate() {
+                for (source_index, source) in target.sources.iter().enumerate() {
+                    let path = paths::resolve(&index.source_dir, &source.path);
+                    let entry =
+                        index
+                            .entries
+                            .entry(index.key(&path))
+                            .or_insert_with(|| IndexedSource {
+                                path: PathBuf::from(&path),
+                                usages: Vec::new(),
+                            });
+                    entry.usages.push(SourceUsage {
+                        configuration: config.name.clone(),
+                        configuration_index,
+                        target: target.name.clone(),
+                        target_index: TargetIdx::new(target_index),
+                        source_index: SourceIdx::new(source_index),
+                        compile_group_index: source.compile_group_index,
+                    });
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Usages of a source file
+    ///
+    /// Relative paths are resolved against the top-level source directory.
+    pub fn lookup<P: AsRef<Path>>(&self, path: P) -> &[SourceUsage] {
+        self.entries
+            .get(&self.key(&paths::resolve(&self.source_dir, path.as_ref())))
+            .map_or(&[], |entry| entry.usages.as_slice())
+    }
+
+    /// Usages of a source file restricted to a single configuration
+    pub fn lookup_in<P: AsRef<Path>>(&self, path: P, configuration: &str) -> Vec<&SourceUsage> {
+        self.lookup(path)
+            .iter()
+            .filter(|usage| usage.configuration == configuration)
+            .collect()
+    }
+
+    /// All indexed sources located in a directory or one of its subdirectories
+    ///
+    /// Relative paths are resolved against the top-level source directory.
+    pub fn under_directory<P: AsRef<Path>>(&self, dir: P) -> Vec<&IndexedSource> {
+        let prefix = self.key(&paths::resolve(&self.source_dir, dir.as_ref()));
+        self.entries
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .filter(|(key, _)| paths::starts_with(key, &prefix))
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// All indexed sources, ordered by path
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedSource> {
+        self.entries.values()
+    }
+
+    /// Number of distinct source files
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn key(&self, normalized: &str) -> String {
+        paths::case_key(normalized, self.case_sensitive)
+    }
+}
+
+impl CodeModel {
+    /// Build a reverse index from source files to targets, see [`SourceIndex`]
+    #[must_use]
+    pub fn source_index(&self) -> SourceIndex {
+        SourceIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::source_index::*;
+    use crate::objects::codemodel_v2::Configuration;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_source_index() {
+        let config = |name: &str| Configuration {
+            name: name.into(),
+            targets: vec![
+                target(json!({
+                    "name": "app",
+                    "sources": [
+                        { "path": "src/main.cpp", "compileGroupIndex": 0 },
+                        { "path": "src/util/../common.cpp", "compileGroupIndex": 0 }
+                    ]
+                })),
+                target(json!({
+                    "name": "lib",
+                    "sources": [
+                        { "path": "src/common.cpp", "compileGroupIndex": 0 },
+                        { "path": "/external/lib/Ext.cpp", "compileGroupIndex": 0 }
+                    ]
+                })),
+            ],
+            ..Default::default()
+        };
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/project".into();
+        codemodel.configurations = vec![config("Debug"), config("Release")];
+
+        let index = SourceIndex::with_case_sensitivity(&codemodel, true);
+        assert_eq!(index.len(), 3);
+
+        let usages = index.lookup("/project/src/common.cpp");
+        assert_eq!(usages.len(), 4);
+        assert_eq!(usages[0].target, "app");
+        assert_eq!(usages[0].source_index, SourceIdx::new(1));
+        assert_eq!(usages[1].target, "lib");
+        assert_eq!(usages[1].source_index, SourceIdx::new(0));
+        assert_eq!(index.lookup_in("src/common.cpp", "Release").len(), 2);
+        assert_eq!(
+            usages[0].source(&codemodel).unwrap().path,
+            PathBuf::from("src/util/../common.cpp")
+        );
+
+        assert!(index.lookup("/external/lib/ext.cpp").is_empty());
+        let insensitive = SourceIndex::with_case_sensitivity(&codemodel, false);
+        assert_eq!(insensitive.lookup("/EXTERNAL/lib/ext.cpp").len(), 2);
+
+        let under_src: Vec<_> = index
+            .under_directory("src")
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect();
+        assert_eq!(
+            under_src,
+            vec![
+                PathBuf::from("/project/src/common.cpp"),
+                PathBuf::from("/project/src/main.cpp")
+            ]
+        );
+        assert!(index.under_directory("/project/sr").is_empty());
+    }
+
+    #[test]
+    fn test_non_ascii_path() {
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/项目".into();
+        codemodel.configurations = vec![Configuration {
+            targets: vec![Target {
+                name: "app".into(),
+                sources: vec![Source {
+                    path: "中文/main.cpp".into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        let index = codemodel.source_index();
+        assert_eq!(index.lookup("/项目/中文/main.cpp").len(), 1);
+        assert_eq!(index.under_directory("中文").len(), 1);
+    }
+}
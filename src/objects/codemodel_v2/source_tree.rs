@@ -0,0 +1,217 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::codemodel::Configuration;
+use super::index::SourceIdx;
+use super::target::{Source, Target};
+use serde::{Deserialize, Serialize};
+
+/// A source group of a target with its subgroups and resolved sources
+///
+/// Source group names use `\` as hierarchy separator, e.g. `Source Files\core` from `source_group(TREE ...)`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourceGroupNode {
+    /// Last component of the group name, empty for the root node.
+    pub name: String,
+
+    /// Full group name with `\` separators, empty for the root node.
+    pub path: String,
+
+    /// Subgroups in order of appearance.
+    pub groups: Vec<SourceGroupNode>,
+
+    /// Sources of the group.
+    pub sources: Vec<SourceEntry>,
+}
+
+/// A source of a source group
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourceEntry {
+    /// 0-based index into the sources of the target.
+    pub source_index: SourceIdx,
+
+    pub source: Source,
+}
+
+/// A target folder (FOLDER target property) with its subfolders and targets
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FolderNode {
+    /// Last component of the folder name, empty for the root node.
+    pub name: String,
+
+    /// Full folder name with `/` separators, empty for the root node.
+    pub path: String,
+
+    /// Subfolders in order of appearance.
+    pub folders: Vec<FolderNode>,
+
+    /// Names of the targets in the folder.
+    pub targets: Vec<String>,
+}
+
+impl SourceGroupNode {
+    /// Find a (nested) group by its full name, e.g. `Source Files\core`
+    #[must_use]
+    pub fn find(&self, path: &str) -> Option<&SourceGroupNode> {
+        path.split('\\')
+            .filter(|component| !component.is_empty())
+            .try_fold(self, |node, component| {
+                node.groups.iter().find(|group| group.name == component)
+            })
+    }
+
+    fn child(&mut self, name: &str) -> &mut SourceGroupNode {
+        let position = self
+            .groups
+            .iter()
+            .position(|group| group.name == name)
+            .unwrap_or_else(|| {
+                let path = if self.path.is_empty() {
+                    name.to_owned()
+                } else {
+                    format!("{}\\{name}", self.path)
+                };
+                self.groups.push(SourceGroupNode {
+                    name: name.to_owned(),
+                    path,
+                    ..Default::default()
+                });
+                self.groups.len() - 1
+            });
+        &mut self.groups[position]
+    }
+}
+
+impl FolderNode {
+    /// Find a (nested) folder by its full name, e.g. `libs/core`
+    #[must_use]
+    pub fn find(&self, path: &str) -> Option<&FolderNode> {
+        path.split('/')
+            .filter(|component| !component.is_empty())
+            .try_fold(self, |node, component| {
+                node.folders.iter().find(|folder| folder.name == component)
+            })
+    }
+
+    fn child(&mut self, name: &str) -> &mut FolderNode {
+        let position = self
+            .folders
+            .iter()
+            .position(|folder| folder.name == name)
+            .unwrap_or_else(|| {
+                let path = if self.path.is_empty() {
+                    name.to_owned()
+                } else {
+                    format!("{}/{name}", self.path)
+                };
+                self.folders.push(FolderNode {
+                    name: name.to_owned(),
+                    path,
+                    ..Default::default()
+                });
+                self.folders.len() - 1
+            });
+        &mut self.folders[position]
+    }
+}
+
+impl Target {
+    /// Nested tree of the source groups of the target
+    ///
+    /// Invalid source indices are skipped.
+    #[must_use]
+    pub fn source_group_tree(&self) -> SourceGroupNode {
+        let mut root = SourceGroupNode::default();
+        for group in &self.source_groups {
+            let node = group
+                .name
+                .split('\\')
+                .filter(|component| !component.is_empty())
+                .fold(&mut root, |parent, component| parent.child(component));
+            node.sources
+                .extend(group.source_indexes.iter().filter_map(|&source_index| {
+                    let source = self.source(source_index)?;
+                    Some(SourceEntry {
+                        source_index,
+                        source: source.clone(),
+                    })
+                }));
+        }
+        root
+    }
+}
+
+impl Configuration {
+    /// Folder tree of all targets, targets without FOLDER property are in the root node
+    #[must_use]
+    pub fn folder_tree(&self) -> FolderNode {
+        let mut root = FolderNode::default();
+        for target in &self.targets {
+            let folder_name = target
+                .folder
+                .as_ref()
+                .map_or("", |folder| folder.name.as_str());
+            let node = folder_name
+                .split('/')
+                .filter(|component| !component.is_empty())
+                .fold(&mut root, |parent, component| parent.child(component));
+            node.targets.push(target.name.clone());
+        }
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::source_tree::*;
+    use crate::reply::fixture::target;
+    use serde_json::json;
+
+    #[test]
+    fn test_source_tree() {
+        let core_target = target(json!({
+            "name": "core",
+            "type": "STATIC_LIBRARY",
+            "sources": [
+                { "path": "src/core/a.cpp" },
+                { "path": "src/core/b.cpp" },
+                { "path": "include/a.h" },
+                { "path": "CMakeLists.txt" }
+            ],
+            "sourceGroups": [
+                { "name": "Source Files\\core", "sourceIndexes": [0, 1] },
+                { "name": "Header Files", "sourceIndexes": [2, 42] },
+                { "name": "", "sourceIndexes": [3] }
+            ]
+        }));
+
+        let tree = core_target.source_group_tree();
+        assert_eq!(tree.sources.len(), 1);
+        assert_eq!(tree.groups.len(), 2);
+        let core = tree.find("Source Files\\core").unwrap();
+        assert_eq!(core.path, "Source Files\\core");
+        assert_eq!(core.sources[1].source_index, SourceIdx::new(1));
+        assert_eq!(
+            tree.find("Header Files").unwrap().sources[0].source.path,
+            std::path::PathBuf::from("include/a.h")
+        );
+
+        let config = Configuration {
+            targets: vec![
+                target(json!({ "name": "core", "folder": { "name": "libs/base" } })),
+                target(json!({ "name": "util", "folder": { "name": "libs" } })),
+                target(json!({ "name": "app" })),
+            ],
+            ..Default::default()
+        };
+        let folders = config.folder_tree();
+        assert_eq!(folders.targets, vec!["app"]);
+        assert_eq!(folders.find("libs").unwrap().targets, vec!["util"]);
+        assert_eq!(folders.find("libs/base").unwrap().targets, vec!["core"]);
+    }
+}
@@ -2,6 +2,7 @@
 #![allow(clippy::redundant_closure_for_method_calls)]
 
 use super::backtrace_graph::BacktraceGraph;
+use super::index::{BacktraceIdx, CompileGroupIdx, FileSetIdx, SourceGroupIdx, SourceIdx};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -32,7 +33,7 @@ pub struct Target {
     /// Optional member that is present when a CMake language backtrace to the command in
     /// the source code that created the target is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
 
     /// Optional member that is present when the FOLDER target property is set.
     pub folder: Option<Folder>,
@@ -62,6 +63,10 @@ pub struct Target {
     #[serde(default)]
     pub launchers: Vec<Launcher>,
 
+    /// Optional member that is present when the target has settings relevant to debugging applications.
+    /// This field was added in codemodel version 2.8.
+    pub debugger: Option<Debugger>,
+
     /// Optional member that is present for executables and shared library targets that link into a runtime binary.
     pub link: Option<Link>,
 
@@ -90,6 +95,11 @@ pub struct Target {
 
     /// A "codemodel" version 2 "backtrace graph" whose nodes are referenced from backtrace members elsewhere in this "target" object.
     pub backtrace_graph: BacktraceGraph,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -98,6 +108,11 @@ pub struct Target {
 pub struct Folder {
     /// A string specifying the name of the target folder.
     pub name: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -115,6 +130,11 @@ pub struct TargetPaths {
     /// relative to that directory (with . for the top-level build directory itself).
     /// Otherwise, the path is absolute.
     pub source: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -126,6 +146,11 @@ pub struct Artifact {
     /// relative to that directory.
     /// Otherwise, the path is absolute.
     pub path: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -137,6 +162,11 @@ pub struct Install {
     /// installation destination paths
     #[serde(default)]
     pub destinations: Vec<Destination>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -144,6 +174,11 @@ pub struct Install {
 pub struct Prefix {
     /// Path value of CMAKE_INSTALL_PREFIX.
     pub path: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -156,7 +191,12 @@ pub struct Destination {
     /// Optional member that is present when a CMake language backtrace to the install() command invocation
     /// that specified this destination is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -175,7 +215,27 @@ pub struct Launcher {
     /// The value is one of the following:
     ///  * emulator: An emulator for the target platform when cross-compiling. See the CROSSCOMPILING_EMULATOR target property.
     /// * test: A start program for the execution of tests. See the TEST_LAUNCHER target property.
+    #[serde(rename = "type")]
     pub launcher_type: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Debugger {
+    /// Optional member that is present when the DEBUGGER_WORKING_DIRECTORY target property is set.
+    /// The value is the directory path, represented with forward slashes.
+    pub working_directory: Option<PathBuf>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -196,6 +256,11 @@ pub struct Link {
     /// Optional member that is present when the CMAKE_SYSROOT_LINK or CMAKE_SYSROOT variable is defined.
     #[serde(default)]
     pub sysroot: Option<SysRootPath>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -209,6 +274,11 @@ pub struct CommandFragment {
     /// A string specifying the role of the fragment's content:
     ///  * flags: archiver flags
     pub role: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -217,6 +287,11 @@ pub struct CommandFragment {
 pub struct SysRootPath {
     /// Absolute path to the sysroot, represented with forward slashes.
     pub path: PathBuf,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -230,6 +305,11 @@ pub struct Archive {
     /// True when link-time optimization (a.k.a. interprocedural optimization or link-time code generation) is enabled.
     #[serde(default)]
     pub lto: bool,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -243,7 +323,12 @@ pub struct Dependency {
     /// Optional member that is present when a CMake language backtrace to the add_dependencies(), target_link_libraries(),
     /// or other command invocation that created this dependency is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -265,6 +350,11 @@ pub struct FileSet {
     /// relative to that directory.
     /// Otherwise, the path is absolute.
     pub base_directories: Vec<String>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -278,11 +368,11 @@ pub struct Source {
 
     /// Optional member that is present when the source is compiled.
     /// The value is an unsigned integer 0-based index into the compileGroups array.
-    pub compile_group_index: Option<usize>,
+    pub compile_group_index: Option<CompileGroupIdx>,
 
     /// Optional member that is present when the source is part of a source group either via the source_group() command or by default.
     /// The value is an unsigned integer 0-based index into the sourceGroups array.
-    pub source_group_index: Option<usize>,
+    pub source_group_index: Option<SourceGroupIdx>,
 
     /// True if the source is GENERATED.
     #[serde(default)]
@@ -291,12 +381,17 @@ pub struct Source {
     /// Optional member that is present when the source is part of a file set.
     /// The value is an unsigned integer 0-based index into the fileSets array.
     /// This field was added in codemodel version 2.5.
-    pub file_set_index: Option<usize>,
+    pub file_set_index: Option<FileSetIdx>,
 
     /// Optional member that is present when a CMake language backtrace to the target_sources(), add_executable(), add_library(),
     /// add_custom_target(), or other command invocation that added this source to the target is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -308,7 +403,12 @@ pub struct SourceGroup {
 
     /// Indices to sources belonging to the group.
     /// Each entry is an unsigned integer 0-based index into the main sources array for the target.
-    pub source_indexes: Vec<usize>,
+    pub source_indexes: Vec<SourceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -316,7 +416,7 @@ pub struct SourceGroup {
 #[non_exhaustive]
 pub struct CompileGroup {
     /// Indices to sources belonging to the compile-group.
-    pub source_indexes: Vec<usize>,
+    pub source_indexes: Vec<SourceIdx>,
 
     /// A string specifying the language (e.g. C, CXX, Fortran) of the toolchain is used to compile the source file.
     pub language: String,
@@ -349,6 +449,11 @@ pub struct CompileGroup {
 
     /// Optional member that is present when the `CMAKE_SYSROOT_COMPILE` or `CMAKE_SYSROOT` variable is defined.
     pub sysroot: Option<SysRootPath>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -360,10 +465,15 @@ pub struct LanguageStandard {
     /// It's possible for multiple compile features to require the same language standard so there could be multiple backtraces.
     /// Each entry being an unsigned integer 0-based index into the backtraceGraph member's nodes array.
     #[serde(default)]
-    pub backtraces: Vec<usize>,
+    pub backtraces: Vec<BacktraceIdx>,
 
     /// String representing the language standard.
     pub standard: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -373,6 +483,11 @@ pub struct CompileCommandFragment {
     /// A string specifying a fragment of the compile command line invocation.
     /// The value is encoded in the build system's native shell format.
     pub fragment: String,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -389,7 +504,12 @@ pub struct Include {
     /// Optional member that is present when a CMake language backtrace to the target_include_directories() or
     /// other command invocation that added this include directory is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -405,7 +525,12 @@ pub struct Framework {
     /// Optional member that is present when a CMake language backtrace to the target_link_libraries() or
     /// other command invocation that added this framework is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -418,7 +543,12 @@ pub struct PrecompileHeader {
     /// Optional member that is present when a CMake language backtrace to the target_precompile_headers() or
     /// other command invocation that added this precompiled header is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -431,7 +561,38 @@ pub struct Define {
     /// Optional member that is present when a CMake language backtrace to the target_compile_definitions() or
     /// other command invocation that added this preprocessor definition is available.
     /// The value is an unsigned integer 0-based index into the backtraceGraph member's nodes array.
-    pub backtrace: Option<usize>,
+    pub backtrace: Option<BacktraceIdx>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+impl Target {
+    /// Source by index, `None` if the index is out of range
+    #[must_use]
+    pub fn source(&self, index: SourceIdx) -> Option<&Source> {
+        self.sources.get(index.get())
+    }
+
+    /// Compile group by index, `None` if the index is out of range
+    #[must_use]
+    pub fn compile_group(&self, index: CompileGroupIdx) -> Option<&CompileGroup> {
+        self.compile_groups.get(index.get())
+    }
+
+    /// Source group by index, `None` if the index is out of range
+    #[must_use]
+    pub fn source_group(&self, index: SourceGroupIdx) -> Option<&SourceGroup> {
+        self.source_groups.get(index.get())
+    }
+
+    /// File set by index, `None` if the index is out of range
+    #[must_use]
+    pub fn file_set(&self, index: FileSetIdx) -> Option<&FileSet> {
+        self.file_sets.get(index.get())
+    }
 }
 
 impl CompileGroup {
@@ -532,14 +693,15 @@ mod tests {
         assert_eq!(
             target,
             Target {
-                backtrace: Some(0),
+                backtrace: Some(0.into()),
                 backtrace_graph: BacktraceGraph {
                     commands: vec![],
                     files: vec!["CMakeLists.txt".into()],
                     nodes: vec![Node {
-                        file: 0,
+                        file: 0.into(),
                         ..Default::default()
-                    }]
+                    }],
+                    ..Default::default()
                 },
                 dependencies: vec![
                     Dependency {
@@ -556,7 +718,8 @@ mod tests {
                 name: "ALL_BUILD".to_string(),
                 paths: TargetPaths {
                     build: ".".into(),
-                    source: ".".into()
+                    source: ".".into(),
+                    ..Default::default()
                 },
                 sources: vec![],
                 type_name: "UTILITY".to_string(),
@@ -564,4 +727,18 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_launcher() {
+        let json = json!({
+            "command" : "/usr/bin/qemu-aarch64",
+            "arguments" : [ "-L", "/usr/aarch64-linux-gnu" ],
+            "type" : "emulator"
+        });
+
+        let launcher = serde_json::from_value::<Launcher>(json.clone()).unwrap();
+        assert_eq!(launcher.launcher_type, "emulator");
+        assert_eq!(launcher.arguments, vec!["-L", "/usr/aarch64-linux-gnu"]);
+        assert_eq!(serde_json::to_value(&launcher).unwrap(), json);
+    }
 }
@@ -0,0 +1,605 @@
+#![allow(clippy::module_name_repetitions)]
+
+use super::backtrace_graph::BacktraceGraph;
+use super::codemodel::{CodeModel, Configuration};
+use super::directory::{Directory, TargetIdAndIndex};
+use super::index::BacktraceIdx;
+use super::target::Target;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of an integrity problem found by [`Configuration::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ProblemKind {
+    /// An index points outside of the array it refers to.
+    IndexOutOfRange {
+        field: String,
+        index: usize,
+        len: usize,
+    },
+
+    /// A reference is not matched by the back reference of the referenced entry,
+    /// e.g. a source whose compile group does not list it.
+    Asymmetric { field: String, index: usize },
+
+    /// A target id that does not exist in the configuration.
+    UnknownTargetId { field: String, id: String },
+
+    /// A target id and index that refer to different targets.
+    TargetIdMismatch {
+        field: String,
+        id: String,
+        index: usize,
+    },
+
+    /// The number of resolved objects differs from the number of references.
+    CountMismatch {
+        field: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// A single integrity problem of a reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ValidationProblem {
+    /// Name of the configuration, e.g. Debug.
+    pub configuration: String,
+
+    /// Object containing the broken reference, e.g. `target app` or `directory src`.
+    pub object: String,
+
+    /// What is wrong.
+    pub kind: ProblemKind,
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: ", self.configuration, self.object)?;
+        match &self.kind {
+            ProblemKind::IndexOutOfRange { field, index, len } => {
+                write!(f, "{field} {index} is out of range (length {len})")
+            }
+            ProblemKind::Asymmetric { field, index } => {
+                write!(f, "{field} {index} has no matching back reference")
+            }
+            ProblemKind::UnknownTargetId { field, id } => {
+                write!(f, "{field} `{id}` does not exist")
+            }
+            ProblemKind::TargetIdMismatch { field, id, index } => {
+                write!(f, "{field} `{id}` does not match target index {index}")
+            }
+            ProblemKind::CountMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(f, "{field} has {actual} entries, expected {expected}"),
+        }
+    }
+}
+
+impl CodeModel {
+    /// Check every cross-reference of all configurations, see [`Configuration::validate`]
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationProblem> {
+        self.configurations
+            .iter()
+            .flat_map(Configuration::validate)
+            .collect()
+    }
+}
+
+impl Configuration {
+    /// Check every cross-reference of the configuration
+    ///
+    /// Covers the project, directory and target hierarchy, sources and their groups, target dependencies,
+    /// backtrace indices and installer targets. An empty list means the configuration is consistent.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationProblem> {
+        let mut validator = Validator {
+            config: self,
+            problems: Vec::new(),
+        };
+        validator.hierarchy();
+        validator.objects();
+        validator.problems
+    }
+}
+
+struct Validator<'a> {
+    config: &'a Configuration,
+    problems: Vec<ValidationProblem>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, object: &str, kind: ProblemKind) {
+        self.problems.push(ValidationProblem {
+            configuration: self.config.name.clone(),
+            object: object.to_owned(),
+            kind,
+        });
+    }
+
+    /// Report an out of range index, returns true if the index is valid
+    fn in_range(&mut self, object: &str, field: &str, index: usize, len: usize) -> bool {
+        if index < len {
+            return true;
+        }
+        self.report(
+            object,
+            ProblemKind::IndexOutOfRange {
+                field: field.to_owned(),
+                index,
+                len,
+            },
+        );
+        false
+    }
+
+    fn asymmetric(&mut self, object: &str, field: &str, index: usize) {
+        self.report(
+            object,
+            ProblemKind::Asymmetric {
+                field: field.to_owned(),
+                index,
+            },
+        );
+    }
+
+    fn hierarchy(&mut self) {
+        let config = self.config;
+        let projects = config.projects.len();
+        let directories = config.directory_refs.len();
+        let targets = config.target_refs.len();
+
+        for (index, project) in config.projects.iter().enumerate() {
+            let object = format!("project {}", project.name);
+            if let Some(parent) = project.parent_index {
+                if self.in_range(&object, "parentIndex", parent.get(), projects)
+                    && !config.projects[parent.get()]
+                        .child_indexes
+                        .iter()
+                        .any(|child| child.get() == index)
+                {
+                    self.asymmetric(&object, "parentIndex", parent.get());
+                }
+            }
+            for child in &project.child_indexes {
+                if self.in_range(&object, "childIndexes", child.get(), projects)
+                    && config.projects[child.get()]
+                        .parent_index
+                        .map(|parent| parent.get())
+                        != Some(index)
+                {
+                    self.asymmetric(&object, "childIndexes", child.get());
+                }
+            }
+            for directory in &project.directory_indexes {
+                if self.in_range(&object, "directoryIndexes", directory.get(), directories)
+                    && config.directory_refs[directory.get()].project_index.get() != index
+                {
+                    self.asymmetric(&object, "directoryIndexes", directory.get());
+                }
+            }
+            for target in &project.target_indexes {
+                if self.in_range(&object, "targetIndexes", target.get(), targets)
+                    && config.target_refs[target.get()].project_index.get() != index
+                {
+                    self.asymmetric(&object, "targetIndexes", target.get());
+                }
+            }
+        }
+
+        for (index, directory) in config.directory_refs.iter().enumerate() {
+            let object = format!("directory {}", directory.source.display());
+            if let Some(parent) = directory.parent_index {
+                if self.in_range(&object, "parentIndex", parent.get(), directories)
+                    && !config.directory_refs[parent.get()]
+                        .child_indexes
+                        .iter()
+                        .any(|child| child.get() == index)
+                {
+                    self.asymmetric(&object, "parentIndex", parent.get());
+                }
+            }
+            for child in &directory.child_indexes {
+                if self.in_range(&object, "childIndexes", child.get(), directories)
+                    && config.directory_refs[child.get()]
+                        .parent_index
+                        .map(|parent| parent.get())
+                        != Some(index)
+                {
+                    self.asymmetric(&object, "childIndexes", child.get());
+                }
+            }
+            self.in_range(
+                &object,
+                "projectIndex",
+                directory.project_index.get(),
+                projects,
+            );
+            for target in &directory.target_indexes {
+                if self.in_range(&object, "targetIndexes", target.get(), targets)
+                    && config.target_refs[target.get()].directory_index.get() != index
+                {
+                    self.asymmetric(&object, "targetIndexes", target.get());
+                }
+            }
+        }
+
+        for target in &config.target_refs {
+            let object = format!("target {}", target.name);
+            self.in_range(
+                &object,
+                "directoryIndex",
+                target.directory_index.get(),
+                directories,
+            );
+            self.in_range(
+                &object,
+                "projectIndex",
+                target.project_index.get(),
+                projects,
+            );
+        }
+    }
+
+    fn objects(&mut self) {
+        let config = self.config;
+        // the objects are only available if the references were resolved
+        if !config.targets.is_empty() && config.targets.len() != config.target_refs.len() {
+            self.report(
+                "configuration",
+                ProblemKind::CountMismatch {
+                    field: "targets".into(),
+                    expected: config.target_refs.len(),
+                    actual: config.targets.len(),
+                },
+            );
+        }
+        if !config.directories.is_empty() && config.directories.len() != config.directory_refs.len()
+        {
+            self.report(
+                "configuration",
+                ProblemKind::CountMismatch {
+                    field: "directories".into(),
+                    expected: config.directory_refs.len(),
+                    actual: config.directories.len(),
+                },
+            );
+        }
+
+        for target in &config.targets {
+            self.target(target);
+        }
+        for (directory, directory_ref) in config.directories.iter().zip(&config.directory_refs) {
+            self.directory(
+                directory,
+                &format!("directory {}", directory_ref.source.display()),
+            );
+        }
+    }
+
+    fn target(&mut self, target: &Target) {
+        let object = format!("target {}", target.name);
+        let sources = target.sources.len();
+
+        for (index, source) in target.sources.iter().enumerate() {
+            if let Some(group) = source.compile_group_index {
+                if self.in_range(
+                    &object,
+                    "compileGroupIndex",
+                    group.get(),
+                    target.compile_groups.len(),
+                ) && !target.compile_groups[group.get()]
+                    .source_indexes
+                    .iter()
+                    .any(|source_index| source_index.get() == index)
+                {
+                    self.asymmetric(&object, "compileGroupIndex", group.get());
+                }
+            }
+            if let Some(group) = source.source_group_index {
+                if self.in_range(
+                    &object,
+                    "sourceGroupIndex",
+                    group.get(),
+                    target.source_groups.len(),
+                ) && !target.source_groups[group.get()]
+                    .source_indexes
+                    .iter()
+                    .any(|source_index| source_index.get() == index)
+                {
+                    self.asymmetric(&object, "sourceGroupIndex", group.get());
+                }
+            }
+            if let Some(file_set) = source.file_set_index {
+                self.in_range(
+                    &object,
+                    "fileSetIndex",
+                    file_set.get(),
+                    target.file_sets.len(),
+                );
+            }
+        }
+
+        for (index, group) in target.compile_groups.iter().enumerate() {
+            for source in &group.source_indexes {
+                if self.in_range(
+                    &object,
+                    "compileGroups.sourceIndexes",
+                    source.get(),
+                    sources,
+                ) && target.sources[source.get()]
+                    .compile_group_index
+                    .map(|group_index| group_index.get())
+                    != Some(index)
+                {
+                    self.asymmetric(&object, "compileGroups.sourceIndexes", source.get());
+                }
+            }
+        }
+        for (index, group) in target.source_groups.iter().enumerate() {
+            for source in &group.source_indexes {
+                if self.in_range(&object, "sourceGroups.sourceIndexes", source.get(), sources)
+                    && target.sources[source.get()]
+                        .source_group_index
+                        .map(|group_index| group_index.get())
+                        != Some(index)
+                {
+                    self.asymmetric(&object, "sourceGroups.sourceIndexes", source.get());
+                }
+            }
+        }
+
+        for dependency in &target.dependencies {
+            if !self.has_target_id(&dependency.id) {
+                self.report(
+                    &object,
+                    ProblemKind::UnknownTargetId {
+                        field: "dependencies".into(),
+                        id: dependency.id.clone(),
+                    },
+                );
+            }
+        }
+
+        let mut backtraces: Vec<Option<BacktraceIdx>> = vec![target.backtrace];
+        backtraces.extend(target.dependencies.iter().map(|dep| dep.backtrace));
+        backtraces.extend(target.sources.iter().map(|source| source.backtrace));
+        if let Some(install) = &target.install {
+            backtraces.extend(install.destinations.iter().map(|dest| dest.backtrace));
+        }
+        for group in &target.compile_groups {
+            backtraces.extend(group.includes.iter().map(|include| include.backtrace));
+            backtraces.extend(group.frameworks.iter().map(|framework| framework.backtrace));
+            backtraces.extend(group.precompile_headers.iter().map(|pch| pch.backtrace));
+            backtraces.extend(group.defines.iter().map(|define| define.backtrace));
+            if let Some(standard) = &group.language_standard {
+                backtraces.extend(standard.backtraces.iter().copied().map(Some));
+            }
+        }
+        self.backtraces(&object, &target.backtrace_graph, backtraces);
+    }
+
+    fn directory(&mut self, directory: &Directory, object: &str) {
+        let targets = self.config.target_refs.len();
+        for installer in &directory.installers {
+            if let Some(index) = installer.target_index {
+                if self.in_range(object, "installers.targetIndex", index.get(), targets) {
+                    if let Some(id) = &installer.target_id {
+                        self.target_id_matches(object, "installers.targetId", id, index.get());
+                    }
+                }
+            }
+            for target in &installer.export_targets {
+                self.target_id_and_index(object, "installers.exportTargets", target);
+            }
+            if let Some(target) = &installer.file_set_target {
+                self.target_id_and_index(object, "installers.fileSetTarget", target);
+            }
+            if let Some(target) = &installer.cxx_module_bmi_target {
+                self.target_id_and_index(object, "installers.cxxModuleBmiTarget", target);
+            }
+        }
+
+        let backtraces = directory
+            .installers
+            .iter()
+            .map(|installer| installer.backtrace)
+            .collect();
+        self.backtraces(object, &directory.backtrace_graph, backtraces);
+    }
+
+    fn backtraces(
+        &mut self,
+        object: &str,
+        graph: &BacktraceGraph,
+        backtraces: Vec<Option<BacktraceIdx>>,
+    ) {
+        let nodes = graph.nodes.len();
+        for backtrace in backtraces.into_iter().flatten() {
+            self.in_range(object, "backtrace", backtrace.get(), nodes);
+        }
+        for node in &graph.nodes {
+            self.in_range(
+                object,
+                "backtraceGraph.nodes.file",
+                node.file.get(),
+                graph.files.len(),
+            );
+            if let Some(command) = node.command {
+                self.in_range(
+                    object,
+                    "backtraceGraph.nodes.command",
+                    command.get(),
+                    graph.commands.len(),
+                );
+            }
+            if let Some(parent) = node.parent {
+                self.in_range(object, "backtraceGraph.nodes.parent", parent.get(), nodes);
+            }
+        }
+    }
+
+    fn has_target_id(&self, id: &str) -> bool {
+        self.config.target_refs.iter().any(|target| target.id == id)
+    }
+
+    fn target_id_and_index(&mut self, object: &str, field: &str, target: &TargetIdAndIndex) {
+        if self.in_range(
+            object,
+            field,
+            target.index.get(),
+            self.config.target_refs.len(),
+        ) {
+            self.target_id_matches(object, field, &target.id, target.index.get());
+        }
+    }
+
+    fn target_id_matches(&mut self, object: &str, field: &str, id: &str, index: usize) {
+        if self.config.target_refs[index].id == id {
+            return;
+        }
+        let kind = if self.has_target_id(id) {
+            ProblemKind::TargetIdMismatch {
+                field: field.to_owned(),
+                id: id.to_owned(),
+                index,
+            }
+        } else {
+            ProblemKind::UnknownTargetId {
+                field: field.to_owned(),
+                id: id.to_owned(),
+            }
+        };
+        self.report(object, kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::validate::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let mut config: Configuration = serde_json::from_value(json!({
+            "name": "Debug",
+            "projects": [
+                { "name": "root", "childIndexes": [1], "directoryIndexes": [0], "targetIndexes": [0] },
+                { "name": "sub", "directoryIndexes": [0, 4] }
+            ],
+            "directories": [
+                { "source": ".", "build": ".", "projectIndex": 0, "targetIndexes": [0],
+                  "jsonFile": "directory-.json" }
+            ],
+            "targets": [
+                { "name": "app", "id": "app::@1", "directoryIndex": 0, "projectIndex": 0, "jsonFile": "app.json" }
+            ]
+        }))
+        .unwrap();
+        config.targets.push(
+            serde_json::from_value(json!({
+                "name": "app",
+                "id": "app::@1",
+                "type": "EXECUTABLE",
+                "backtrace": 3,
+                "backtraceGraph": { "commands": [], "files": ["CMakeLists.txt"], "nodes": [{ "file": 0 }] },
+                "paths": { "source": ".", "build": "." },
+                "dependencies": [{ "id": "missing::@1" }],
+                "sources": [
+                    { "path": "main.cpp", "compileGroupIndex": 0 },
+                    { "path": "util.cpp", "compileGroupIndex": 1 }
+                ],
+                "compileGroups": [{ "language": "CXX", "sourceIndexes": [0] }]
+            }))
+            .unwrap(),
+        );
+        config.directories.push(
+            serde_json::from_value(json!({
+                "backtraceGraph": { "commands": [], "files": [], "nodes": [] },
+                "installers": [
+                    { "component": "Runtime", "type": "target", "targetId": "lib::@1", "targetIndex": 0 }
+                ],
+                "paths": { "build": ".", "source": "." }
+            }))
+            .unwrap(),
+        );
+
+        let problems = config.validate();
+        let kinds: Vec<(&str, &ProblemKind)> = problems
+            .iter()
+            .map(|problem| (problem.object.as_str(), &problem.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    "project root",
+                    &ProblemKind::Asymmetric {
+                        field: "childIndexes".into(),
+                        index: 1
+                    }
+                ),
+                (
+                    "project sub",
+                    &ProblemKind::Asymmetric {
+                        field: "directoryIndexes".into(),
+                        index: 0
+                    }
+                ),
+                (
+                    "project sub",
+                    &ProblemKind::IndexOutOfRange {
+                        field: "directoryIndexes".into(),
+                        index: 4,
+                        len: 1
+                    }
+                ),
+                (
+                    "target app",
+                    &ProblemKind::IndexOutOfRange {
+                        field: "compileGroupIndex".into(),
+                        index: 1,
+                        len: 1
+                    }
+                ),
+                (
+                    "target app",
+                    &ProblemKind::UnknownTargetId {
+                        field: "dependencies".into(),
+                        id: "missing::@1".into()
+                    }
+                ),
+                (
+                    "target app",
+                    &ProblemKind::IndexOutOfRange {
+                        field: "backtrace".into(),
+                        index: 3,
+                        len: 1
+                    }
+                ),
+                (
+                    "directory .",
+                    &ProblemKind::UnknownTargetId {
+                        field: "installers.targetId".into(),
+                        id: "lib::@1".into()
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            problems[3].to_string(),
+            "[Debug] target app: compileGroupIndex 1 is out of range (length 1)"
+        );
+
+        let mut codemodel = CodeModel::default();
+        codemodel.configurations.push(config);
+        assert_eq!(codemodel.validate().len(), 7);
+    }
+}
@@ -20,6 +20,11 @@ pub struct ConfigureLog {
 
     /// Names of the event kinds that are logged in the configure log.
     pub event_kind_names: Vec<String>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 impl Object for ConfigureLog {
@@ -67,7 +72,12 @@ mod tests {
                 ],
                 kind: ObjectKind::ConfigureLog,
                 path: "build/CMakeFiles/CMakeConfigureLog.yaml".into(),
-                version: MajorMinor { major: 1, minor: 0 }
+                version: MajorMinor {
+                    major: 1,
+                    minor: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
             }
         );
     }
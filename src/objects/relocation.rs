@@ -0,0 +1,391 @@
+//! Make reply paths absolute and relocate source and build trees
+//!
+//! Paths in replies are represented with forward slashes and are either absolute or relative to the
+//! top-level source or build directory, depending on the member. [`Relocate`] knows the base of every
+//! path member, so a loaded model can be made independent of these conventions or moved to another
+//! location, e.g. when `/src` in a container is `/home/me/project` on the host.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cmake_file_api::objects::relocation::Relocate;
+//! use cmake_file_api::{objects, reply};
+//! # let build_dir = std::path::Path::new(".");
+//!
+//! let reader = reply::Reader::from_build_dir(build_dir).expect("Failed to read reply");
+//! let mut codemodel: objects::CodeModelV2 = reader.read_object().expect("Failed to read codemodel");
+//! codemodel.relocate("/src", "/home/me/project");
+//! let paths = codemodel.paths.clone();
+//! codemodel.make_paths_absolute(&paths.source, &paths.build);
+//! ```
+
+use crate::objects::codemodel_v2::{CodeModel, Directory, InstallPath, Target};
+use crate::objects::{CMakeFilesV1, ConfigureLogV1, ToolchainsV1};
+use crate::paths;
+use std::path::{Path, PathBuf};
+
+/// Directory that a relative path of a reply is relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathBase {
+    /// Relative to the top-level source directory.
+    Source,
+
+    /// Relative to the top-level build directory.
+    Build,
+
+    /// The path is documented to be absolute.
+    Absolute,
+}
+
+/// Visit, normalize and relocate the file system paths of an object
+pub trait Relocate {
+    /// Call `visitor` for every file system path of the object with the directory it is relative to
+    ///
+    /// Install destinations and `jsonFile` references are not file system paths of the build and are not visited.
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase));
+
+    /// Call `visitor` for every command line fragment of the object, fragments may contain paths like `-I/src/include`
+    fn visit_fragments(&mut self, _visitor: &mut dyn FnMut(&mut String)) {}
+
+    /// Make every path absolute by resolving relative paths against the top-level source or build directory
+    ///
+    /// Paths are lexically normalized and use forward slashes.
+    fn make_paths_absolute(&mut self, source_dir: &Path, build_dir: &Path) {
+        self.visit_paths(&mut |path, base| {
+            let resolved = match base {
+                PathBase::Source => paths::resolve(source_dir, path),
+                PathBase::Build => paths::resolve(build_dir, path),
+                PathBase::Absolute => paths::normalize(path),
+            };
+            *path = PathBuf::from(resolved);
+        });
+    }
+
+    /// Replace the prefix `from` of absolute paths with `to`
+    ///
+    /// Prefixes are matched component-wise, e.g. `/src` does not match `/src2/main.cpp`. Paths inside of
+    /// command line fragments are replaced as well, like [`crate::reply::snapshot::Snapshot`] does.
+    /// Relative paths are kept, they follow the relocated top-level directories.
+    fn relocate<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q)
+    where
+        Self: Sized,
+    {
+        let old = paths::normalize(from.as_ref());
+        let new = paths::normalize(to.as_ref());
+        self.visit_paths(&mut |path, _| {
+            if let Some(relocated) = paths::relocate(&paths::normalize(path), &old, &new) {
+                *path = PathBuf::from(relocated);
+            }
+        });
+        self.visit_fragments(&mut |fragment| {
+            if let Some(relocated) = paths::relocate_fragment(fragment, &old, &new) {
+                *fragment = relocated;
+            }
+        });
+    }
+}
+
+/// Visit a path stored as string
+fn visit_string(
+    path: &mut String,
+    base: PathBase,
+    visitor: &mut dyn FnMut(&mut PathBuf, PathBase),
+) {
+    let mut path_buf = PathBuf::from(path.as_str());
+    visitor(&mut path_buf, base);
+    *path = path_buf.to_string_lossy().into_owned();
+}
+
+impl Relocate for CodeModel {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        visitor(&mut self.paths.source, PathBase::Absolute);
+        visitor(&mut self.paths.build, PathBase::Absolute);
+        for config in &mut self.configurations {
+            for directory_ref in &mut config.directory_refs {
+                visitor(&mut directory_ref.source, PathBase::Source);
+                visitor(&mut directory_ref.build, PathBase::Build);
+            }
+            for directory in &mut config.directories {
+                directory.visit_paths(visitor);
+            }
+            for target in &mut config.targets {
+                target.visit_paths(visitor);
+            }
+        }
+    }
+
+    fn visit_fragments(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        for target in self
+            .configurations
+            .iter_mut()
+            .flat_map(|config| &mut config.targets)
+        {
+            target.visit_fragments(visitor);
+        }
+    }
+}
+
+impl Relocate for Directory {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        visitor(&mut self.paths.source, PathBase::Source);
+        visitor(&mut self.paths.build, PathBase::Build);
+        for file in &mut self.backtrace_graph.files {
+            visitor(file, PathBase::Source);
+        }
+
+        for installer in &mut self.installers {
+            let base = installer.path_base();
+            for install_path in &mut installer.paths {
+                match install_path {
+                    InstallPath::PathCombination(path) => visit_string(path, base, visitor),
+                    InstallPath::FromTo(from_to) => visitor(&mut from_to.from, base),
+                }
+            }
+            for directory in &mut installer.file_set_directories {
+                visit_string(directory, PathBase::Source, visitor);
+            }
+            if let Some(script_file) = &mut installer.script_file {
+                visitor(script_file, PathBase::Source);
+            }
+        }
+    }
+}
+
+impl Relocate for Target {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        visitor(&mut self.paths.source, PathBase::Source);
+        visitor(&mut self.paths.build, PathBase::Build);
+        for artifact in &mut self.artifacts {
+            visitor(&mut artifact.path, PathBase::Build);
+        }
+        if let Some(install) = &mut self.install {
+            visitor(&mut install.prefix.path, PathBase::Absolute);
+        }
+        for launcher in &mut self.launchers {
+            visit_string(&mut launcher.command, PathBase::Source, visitor);
+        }
+        if let Some(working_directory) = self
+            .debugger
+            .as_mut()
+            .and_then(|debugger| debugger.working_directory.as_mut())
+        {
+            visitor(working_directory, PathBase::Absolute);
+        }
+        if let Some(sysroot) = self.link.as_mut().and_then(|link| link.sysroot.as_mut()) {
+            visitor(&mut sysroot.path, PathBase::Absolute);
+        }
+        for file_set in &mut self.file_sets {
+            for directory in &mut file_set.base_directories {
+                visit_string(directory, PathBase::Source, visitor);
+            }
+        }
+        for source in &mut self.sources {
+            visitor(&mut source.path, PathBase::Source);
+        }
+        for group in &mut self.compile_groups {
+            for include in &mut group.includes {
+                visitor(&mut include.path, PathBase::Source);
+            }
+            for framework in &mut group.frameworks {
+                visitor(&mut framework.path, PathBase::Source);
+            }
+            for header in &mut group.precompile_headers {
+                visitor(&mut header.header, PathBase::Source);
+            }
+            if let Some(sysroot) = &mut group.sysroot {
+                visitor(&mut sysroot.path, PathBase::Absolute);
+            }
+        }
+        for file in &mut self.backtrace_graph.files {
+            visitor(file, PathBase::Source);
+        }
+    }
+
+    fn visit_fragments(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        for group in &mut self.compile_groups {
+            for fragment in &mut group.compile_command_fragments {
+                visitor(&mut fragment.fragment);
+            }
+        }
+        let link_fragments = self
+            .link
+            .iter_mut()
+            .flat_map(|link| &mut link.command_fragments);
+        let archive_fragments = self
+            .archive
+            .iter_mut()
+            .flat_map(|archive| &mut archive.command_fragments);
+        for fragment in link_fragments.chain(archive_fragments) {
+            visitor(&mut fragment.fragment);
+        }
+    }
+}
+
+impl Relocate for CMakeFilesV1 {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        visitor(&mut self.paths.source, PathBase::Absolute);
+        visitor(&mut self.paths.build, PathBase::Absolute);
+        for input in &mut self.inputs {
+            visitor(&mut input.path, PathBase::Source);
+        }
+    }
+}
+
+impl Relocate for ToolchainsV1 {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        for toolchain in &mut self.toolchains {
+            let compiler = &mut toolchain.compiler;
+            if let Some(path) = &mut compiler.path {
+                visitor(path, PathBase::Absolute);
+            }
+            let implicit = &mut compiler.implicit;
+            for path in implicit
+                .include_directories
+                .iter_mut()
+                .chain(&mut implicit.link_directories)
+                .chain(&mut implicit.link_framework_directories)
+            {
+                visitor(path, PathBase::Absolute);
+            }
+            // implicit link libraries are mostly names like `stdc++`, only full paths are file system paths
+            for library in implicit
+                .link_libraries
+                .iter_mut()
+                .filter(|library| paths::is_absolute(library))
+            {
+                visitor(library, PathBase::Absolute);
+            }
+        }
+    }
+}
+
+impl Relocate for ConfigureLogV1 {
+    fn visit_paths(&mut self, visitor: &mut dyn FnMut(&mut PathBuf, PathBase)) {
+        visitor(&mut self.path, PathBase::Absolute);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::{Configuration, Target};
+    use crate::objects::relocation::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_relocation() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "paths": { "source": "app", "build": "app" },
+            "artifacts": [ { "path": "app/app" } ],
+            "sources": [ { "path": "app/main.cpp" }, { "path": "/src2/extra.cpp" } ],
+            "compileGroups": [{
+                "language": "CXX",
+                "sourceIndexes": [0, 1],
+                "includes": [ { "path": "/src/include" } ],
+                "compileCommandFragments": [ { "fragment": "-I/src/include -I/src2" } ]
+            }],
+            "link": {
+                "language": "CXX",
+                "commandFragments": [ { "fragment": "-Wl,-rpath,/src/build/lib", "role": "libraries" } ]
+            },
+            "backtraceGraph": { "commands": [], "files": [ "app/CMakeLists.txt" ], "nodes": [] }
+        }))
+        .unwrap();
+
+        let mut codemodel = CodeModel::default();
+        codemodel.paths.source = "/src".into();
+        codemodel.paths.build = "/src/build".into();
+        codemodel.configurations.push(Configuration {
+            targets: vec![target],
+            ..Default::default()
+        });
+
+        codemodel.relocate("/src", "/home/me/project");
+        assert_eq!(
+            codemodel.paths.build,
+            PathBuf::from("/home/me/project/build")
+        );
+        let relocated = &codemodel.configurations[0].targets[0];
+        assert_eq!(relocated.sources[0].path, PathBuf::from("app/main.cpp"));
+        assert_eq!(relocated.sources[1].path, PathBuf::from("/src2/extra.cpp"));
+        assert_eq!(
+            relocated.compile_groups[0].includes[0].path,
+            PathBuf::from("/home/me/project/include")
+        );
+        assert_eq!(
+            relocated.compile_groups[0].compile_command_fragments[0].fragment,
+            "-I/home/me/project/include -I/src2"
+        );
+        assert_eq!(
+            relocated.link.as_ref().unwrap().command_fragments[0].fragment,
+            "-Wl,-rpath,/home/me/project/build/lib"
+        );
+
+        let paths = codemodel.paths.clone();
+        codemodel.make_paths_absolute(&paths.source, &paths.build);
+        let absolute = &codemodel.configurations[0].targets[0];
+        assert_eq!(
+            absolute.paths.build,
+            PathBuf::from("/home/me/project/build/app")
+        );
+        assert_eq!(
+            absolute.artifacts[0].path,
+            PathBuf::from("/home/me/project/build/app/app")
+        );
+        assert_eq!(
+            absolute.sources[0].path,
+            PathBuf::from("/home/me/project/app/main.cpp")
+        );
+        assert_eq!(
+            absolute.backtrace_graph.files[0],
+            PathBuf::from("/home/me/project/app/CMakeLists.txt")
+        );
+    }
+
+    #[test]
+    fn test_relocation_toolchains() {
+        let mut toolchains: ToolchainsV1 = serde_json::from_value(json!({
+            "kind": "toolchains",
+            "version": { "major": 1, "minor": 0 },
+            "toolchains": [{
+                "language": "CXX",
+                "compiler": {
+                    "path": "/usr/bin/c++",
+                    "implicit": {
+                        "includeDirectories": [ "/usr/include" ],
+                        "linkLibraries": [ "stdc++", "/usr/lib/libgcc.a" ]
+                    }
+                }
+            }]
+        }))
+        .unwrap();
+
+        toolchains.relocate("/usr", "/opt/sysroot/usr");
+        let compiler = &toolchains.toolchains[0].compiler;
+        assert_eq!(
+            compiler.path,
+            Some(PathBuf::from("/opt/sysroot/usr/bin/c++"))
+        );
+        assert_eq!(
+            compiler.implicit.include_directories,
+            vec![PathBuf::from("/opt/sysroot/usr/include")]
+        );
+        assert_eq!(
+            compiler.implicit.link_libraries,
+            vec![
+                PathBuf::from("stdc++"),
+                PathBuf::from("/opt/sysroot/usr/lib/libgcc.a")
+            ]
+        );
+
+        // library names are not resolved against any directory
+        toolchains.make_paths_absolute(Path::new("/src"), Path::new("/build"));
+        assert_eq!(
+            toolchains.toolchains[0].compiler.implicit.link_libraries[0],
+            PathBuf::from("stdc++")
+        );
+    }
+}
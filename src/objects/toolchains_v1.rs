@@ -1,6 +1,7 @@
+use crate::objects::codemodel_v2::CodeModel;
 use crate::objects::{MajorMinor, Object, ObjectKind};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// The toolchains object kind lists properties of the toolchains used during the build
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -15,6 +16,11 @@ pub struct Toolchains {
 
     /// Toolchains.
     pub toolchains: Vec<Toolchain>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -31,6 +37,11 @@ pub struct Toolchain {
     /// Each string holds a file extension (without the leading dot) for the language
     #[serde(default)]
     pub source_file_extensions: Vec<String>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -55,6 +66,11 @@ pub struct Compiler {
 
     /// Implicit compiler info for `CMAKE_<LANG>_IMPLICIT_*` variables.
     pub implicit: Implicit,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -80,6 +96,577 @@ pub struct Implicit {
     /// Each path points to an implicit link library for the compiler.
     #[serde(default)]
     pub link_libraries: Vec<PathBuf>,
+
+    /// JSON fields not modeled by this crate, see [`crate::extras`].
+    #[cfg(feature = "extras")]
+    #[serde(flatten, serialize_with = "crate::extras::serialize")]
+    pub extras: crate::extras::Extras,
+}
+
+/// A compiled source file whose extension does not match the language of its compile group.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourceLanguageMismatch {
+    /// Name of the configuration, e.g. Debug.
+    pub configuration: String,
+
+    /// Name of the target that compiles the source.
+    pub target: String,
+
+    /// Path of the source as listed in the target.
+    pub source: PathBuf,
+
+    /// Language of the compile group the source belongs to.
+    pub compile_group_language: String,
+
+    /// Language of the toolchain whose source file extensions contain the extension of the source.
+    /// `None` if the extension does not belong to any enabled language.
+    pub extension_language: Option<String>,
+}
+
+impl Toolchains {
+    /// Find the toolchain for a language, e.g. CXX
+    #[must_use]
+    pub fn toolchain(&self, language: &str) -> Option<&Toolchain> {
+        self.toolchains
+            .iter()
+            .find(|toolchain| toolchain.language == language)
+    }
+
+    /// Find the toolchain that compiles a file based on its extension
+    ///
+    /// Extensions are matched case-sensitively first, since `CMake` distinguishes e.g. `.c` (C) and `.C` (CXX).
+    /// If no toolchain lists the exact extension, a case-insensitive match is used as fallback
+    /// (e.g. `.CPP` on case-insensitive file systems).
+    #[must_use]
+    pub fn toolchain_for_path<P: AsRef<Path>>(&self, path: P) -> Option<&Toolchain> {
+        let extension = path.as_ref().extension()?.to_str()?;
+
+        self.toolchains
+            .iter()
+            .find(|toolchain| {
+                toolchain
+                    .source_file_extensions
+                    .iter()
+                    .any(|ext| ext == extension)
+            })
+            .or_else(|| {
+                self.toolchains.iter().find(|toolchain| {
+                    toolchain
+                        .source_file_extensions
+                        .iter()
+                        .any(|ext| ext.eq_ignore_ascii_case(extension))
+                })
+            })
+    }
+
+    /// Cross-check the language of compiled sources against their file extension
+    ///
+    /// Reports every compiled source whose extension maps to no enabled language or to a language
+    /// different from the language of its compile group, e.g. a `.cu` file compiled as CXX.
+    /// Sources without a compile group (e.g. headers) are not checked.
+    #[must_use]
+    pub fn check_source_languages(&self, codemodel: &CodeModel) -> Vec<SourceLanguageMismatch> {
+        let mut mismatches = Vec::new();
+
+        for config in &codemodel.configurations {
+            for target in &config.targets {
+                for source in &target.sources {
+                    let compile_group = match source
+                        .compile_group_index
+                        .and_then(|index| target.compile_group(index))
+                    {
+                        Some(compile_group) => compile_group,
+                        None => continue,
+                    };
+
+                    let extension_language = self
+                        .toolchain_for_path(&source.path)
+                        .map(|toolchain| toolchain.language.clone());
+
+                    if extension_language.as_ref() != Some(&compile_group.language) {
+                        mismatches.push(SourceLanguageMismatch {
+                            configuration: config.name.clone(),
+                            target: target.name.clone(),
+                            source: source.path.clone(),
+                            compile_group_language: compile_group.language.clone(),
+                            extension_language,
+                        });
+                    }
+                }
+            }
+        }
+
+        mismatches
+    }
+}
+
+/// Compiler identification as reported by `CMAKE_<LANG>_COMPILER_ID`.
+///
+/// Ids that are not known to this crate are kept verbatim in [`CompilerId::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
+pub enum CompilerId {
+    /// Absoft Fortran
+    Absoft,
+    /// Analog VisualDSP++
+    Adsp,
+    /// Apple Clang
+    AppleClang,
+    /// ARM Compiler 5
+    ArmCc,
+    /// ARM Compiler 6 (Clang based)
+    ArmClang,
+    /// Borland, reported by older `CMake` versions for Embarcadero compilers
+    Borland,
+    /// Bruce C Compiler
+    Bruce,
+    /// Concurrent Fortran
+    CCur,
+    /// LLVM Clang
+    Clang,
+    /// Cray Compiler
+    Cray,
+    /// Cray Clang-based Compiler
+    CrayClang,
+    /// Embarcadero
+    Embarcadero,
+    /// Classic Flang Fortran
+    Flang,
+    /// LLVM Flang Fortran
+    LlvmFlang,
+    /// Fujitsu HPC compiler (Trad mode)
+    Fujitsu,
+    /// Fujitsu HPC compiler (Clang mode)
+    FujitsuClang,
+    /// GNU Compiler Collection
+    Gnu,
+    /// Green Hills Software
+    Ghs,
+    /// Hewlett-Packard Compiler
+    Hp,
+    /// IAR Systems
+    Iar,
+    /// Intel Classic Compiler
+    Intel,
+    /// Intel oneAPI LLVM-based Compiler
+    IntelLlvm,
+    /// IBM Clang-based XL / open XL
+    IbmClang,
+    /// MCST Elbrus C/C++/Fortran
+    Lcc,
+    /// LFortran
+    LFortran,
+    /// Microsoft Visual Studio
+    Msvc,
+    /// NVIDIA CUDA Compiler
+    Nvidia,
+    /// NVIDIA HPC Compiler
+    Nvhpc,
+    /// Open Watcom
+    OpenWatcom,
+    /// Orange C
+    OrangeC,
+    /// The Portland Group
+    Pgi,
+    /// `PathScale`
+    PathScale,
+    /// Small Device C Compiler
+    Sdcc,
+    /// Oracle Developer Studio
+    SunPro,
+    /// Tasking Compiler Toolsets
+    Tasking,
+    /// Texas Instruments
+    Ti,
+    /// Tiny C Compiler
+    TinyCc,
+    /// IBM XL, reported by older `CMake` versions
+    VisualAge,
+    /// Open Watcom, reported by older `CMake` versions
+    Watcom,
+    /// IBM XL
+    Xl,
+    /// IBM Clang-based XL
+    XlClang,
+    /// IBM z/OS compiler
+    ZOs,
+    /// Any compiler id that is not known to this crate.
+    Other(String),
+}
+
+impl CompilerId {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            CompilerId::Absoft => "Absoft",
+            CompilerId::Adsp => "ADSP",
+            CompilerId::AppleClang => "AppleClang",
+            CompilerId::ArmCc => "ARMCC",
+            CompilerId::ArmClang => "ARMClang",
+            CompilerId::Borland => "Borland",
+            CompilerId::Bruce => "Bruce",
+            CompilerId::CCur => "CCur",
+            CompilerId::Clang => "Clang",
+            CompilerId::Cray => "Cray",
+            CompilerId::CrayClang => "CrayClang",
+            CompilerId::Embarcadero => "Embarcadero",
+            CompilerId::Flang => "Flang",
+            CompilerId::LlvmFlang => "LLVMFlang",
+            CompilerId::Fujitsu => "Fujitsu",
+            CompilerId::FujitsuClang => "FujitsuClang",
+            CompilerId::Gnu => "GNU",
+            CompilerId::Ghs => "GHS",
+            CompilerId::Hp => "HP",
+            CompilerId::Iar => "IAR",
+            CompilerId::Intel => "Intel",
+            CompilerId::IntelLlvm => "IntelLLVM",
+            CompilerId::IbmClang => "IBMClang",
+            CompilerId::Lcc => "LCC",
+            CompilerId::LFortran => "LFortran",
+            CompilerId::Msvc => "MSVC",
+            CompilerId::Nvidia => "NVIDIA",
+            CompilerId::Nvhpc => "NVHPC",
+            CompilerId::OpenWatcom => "OpenWatcom",
+            CompilerId::OrangeC => "OrangeC",
+            CompilerId::Pgi => "PGI",
+            CompilerId::PathScale => "PathScale",
+            CompilerId::Sdcc => "SDCC",
+            CompilerId::SunPro => "SunPro",
+            CompilerId::Tasking => "Tasking",
+            CompilerId::Ti => "TI",
+            CompilerId::TinyCc => "TinyCC",
+            CompilerId::VisualAge => "VisualAge",
+            CompilerId::Watcom => "Watcom",
+            CompilerId::Xl => "XL",
+            CompilerId::XlClang => "XLClang",
+            CompilerId::ZOs => "zOS",
+            CompilerId::Other(id) => id,
+        }
+    }
+
+    /// True for compilers that are based on the LLVM Clang frontend.
+    #[must_use]
+    pub fn is_clang_based(&self) -> bool {
+        matches!(
+            self,
+            CompilerId::Clang
+                | CompilerId::AppleClang
+                | CompilerId::ArmClang
+                | CompilerId::CrayClang
+                | CompilerId::FujitsuClang
+                | CompilerId::IbmClang
+                | CompilerId::IntelLlvm
+                | CompilerId::XlClang
+        )
+    }
+}
+
+impl From<&str> for CompilerId {
+    fn from(id: &str) -> Self {
+        match id {
+            "Absoft" => CompilerId::Absoft,
+            "ADSP" => CompilerId::Adsp,
+            "AppleClang" => CompilerId::AppleClang,
+            "ARMCC" => CompilerId::ArmCc,
+            "ARMClang" => CompilerId::ArmClang,
+            "Borland" => CompilerId::Borland,
+            "Bruce" => CompilerId::Bruce,
+            "CCur" => CompilerId::CCur,
+            "Clang" => CompilerId::Clang,
+            "Cray" => CompilerId::Cray,
+            "CrayClang" => CompilerId::CrayClang,
+            "Embarcadero" => CompilerId::Embarcadero,
+            "Flang" => CompilerId::Flang,
+            "LLVMFlang" => CompilerId::LlvmFlang,
+            "Fujitsu" => CompilerId::Fujitsu,
+            "FujitsuClang" => CompilerId::FujitsuClang,
+            "GNU" => CompilerId::Gnu,
+            "GHS" => CompilerId::Ghs,
+            "HP" => CompilerId::Hp,
+            "IAR" => CompilerId::Iar,
+            "Intel" => CompilerId::Intel,
+            "IntelLLVM" => CompilerId::IntelLlvm,
+            "IBMClang" => CompilerId::IbmClang,
+            "LCC" => CompilerId::Lcc,
+            "LFortran" => CompilerId::LFortran,
+            "MSVC" => CompilerId::Msvc,
+            "NVIDIA" => CompilerId::Nvidia,
+            "NVHPC" => CompilerId::Nvhpc,
+            "OpenWatcom" => CompilerId::OpenWatcom,
+            "OrangeC" => CompilerId::OrangeC,
+            "PGI" => CompilerId::Pgi,
+            "PathScale" => CompilerId::PathScale,
+            "SDCC" => CompilerId::Sdcc,
+            "SunPro" => CompilerId::SunPro,
+            "Tasking" => CompilerId::Tasking,
+            "TI" => CompilerId::Ti,
+            "TinyCC" => CompilerId::TinyCc,
+            "VisualAge" => CompilerId::VisualAge,
+            "Watcom" => CompilerId::Watcom,
+            "XL" => CompilerId::Xl,
+            "XLClang" => CompilerId::XlClang,
+            "zOS" => CompilerId::ZOs,
+            other => CompilerId::Other(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for CompilerId {
+    fn from(id: String) -> Self {
+        CompilerId::from(id.as_str())
+    }
+}
+
+impl From<CompilerId> for String {
+    fn from(id: CompilerId) -> Self {
+        id.as_str().to_owned()
+    }
+}
+
+impl std::fmt::Display for CompilerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Comparable version in the format `<major>[.<minor>[.<patch>[.<tweak>]]]`.
+/// Missing components are treated as zero, e.g. `11` == `11.0.0.0`.
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub tweak: u64,
+}
+
+impl Version {
+    #[must_use]
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            tweak: 0,
+        }
+    }
+
+    /// Parse a version string like `11.4.0` or `19.38.33133.0`.
+    ///
+    /// Parsing stops at the first component that does not start with a digit; any trailing
+    /// non-digit characters of a component (e.g. `-rc1`) are ignored.
+    /// Returns `None` if not even the major component can be parsed.
+    #[must_use]
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut components = version.trim().split('.').map(|component| {
+            let digits: String = component.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u64>().ok()
+        });
+
+        let major = components.next().flatten()?;
+        let mut rest = [0u64; 3];
+        for slot in &mut rest {
+            match components.next().flatten() {
+                Some(value) => *slot = value,
+                None => break,
+            }
+        }
+
+        Some(Version {
+            major,
+            minor: rest[0],
+            patch: rest[1],
+            tweak: rest[2],
+        })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.tweak != 0 {
+            write!(f, ".{}", self.tweak)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cross-compiling target triple, e.g. `x86_64-pc-windows-msvc` or `arm-linux-gnueabihf`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TargetTriple {
+    /// Architecture, e.g. `x86_64`, `aarch64` or `armv7a`.
+    pub arch: String,
+
+    /// Vendor, e.g. `pc`, `apple`, `unknown` or `none`.
+    pub vendor: Option<String>,
+
+    /// Operating system, e.g. `linux`, `windows` or `darwin`.
+    pub os: Option<String>,
+
+    /// Environment / ABI, e.g. `gnu`, `msvc` or `eabihf`.
+    pub env: Option<String>,
+}
+
+impl TargetTriple {
+    /// Vendors that are recognized when a triple only has three components.
+    const VENDORS: &'static [&'static str] = &[
+        "pc",
+        "apple",
+        "unknown",
+        "none",
+        "w64",
+        "nvidia",
+        "ibm",
+        "scei",
+        "suse",
+        "redhat",
+        "amd",
+        "intel",
+        "mesa",
+        "mti",
+        "img",
+        "fsl",
+        "csr",
+        "myriad",
+        "openembedded",
+        "oe",
+        "poky",
+        "wrs",
+        "espressif",
+        "sony",
+    ];
+
+    /// Parse a target triple like `<arch>-<vendor>-<os>-<env>`.
+    ///
+    /// Three component triples are interpreted as `<arch>-<vendor>-<os>` if the second
+    /// component is a known vendor and as `<arch>-<os>-<env>` otherwise (e.g. `x86_64-linux-gnu`).
+    #[must_use]
+    pub fn parse(triple: &str) -> Option<Self> {
+        let parts: Vec<&str> = triple.trim().split('-').collect();
+        let owned = |index: usize| parts.get(index).map(|part| (*part).to_owned());
+
+        match parts.as_slice() {
+            [""] | [] => None,
+            [arch] => Some(TargetTriple {
+                arch: (*arch).to_owned(),
+                ..Default::default()
+            }),
+            [arch, _] => Some(TargetTriple {
+                arch: (*arch).to_owned(),
+                os: owned(1),
+                ..Default::default()
+            }),
+            [arch, second, _] if Self::VENDORS.contains(second) => Some(TargetTriple {
+                arch: (*arch).to_owned(),
+                vendor: owned(1),
+                os: owned(2),
+                env: None,
+            }),
+            [arch, _, _] => Some(TargetTriple {
+                arch: (*arch).to_owned(),
+                vendor: None,
+                os: owned(1),
+                env: owned(2),
+            }),
+            [arch, ..] => Some(TargetTriple {
+                arch: (*arch).to_owned(),
+                vendor: owned(1),
+                os: owned(2),
+                env: Some(parts[3..].join("-")),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for TargetTriple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.arch)?;
+        for part in [&self.vendor, &self.os, &self.env].into_iter().flatten() {
+            write!(f, "-{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Command line syntax accepted by the compiler driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum FrontendVariant {
+    /// GNU-style flags like `-I`, `-D` and `-std=`, e.g. gcc or clang.
+    Gnu,
+
+    /// MSVC-style flags like `/I`, `/D` and `/std:`, e.g. cl or clang-cl.
+    Msvc,
+}
+
+impl Compiler {
+    /// Parsed compiler id
+    #[must_use]
+    pub fn parsed_id(&self) -> Option<CompilerId> {
+        self.id.as_deref().map(CompilerId::from)
+    }
+
+    /// Parsed compiler version
+    #[must_use]
+    pub fn parsed_version(&self) -> Option<Version> {
+        self.version.as_deref().and_then(Version::parse)
+    }
+
+    /// Parsed cross-compiling target triple
+    #[must_use]
+    pub fn parsed_target(&self) -> Option<TargetTriple> {
+        self.target.as_deref().and_then(TargetTriple::parse)
+    }
+
+    /// Command line syntax of the compiler driver
+    ///
+    /// `CMake` does not report `CMAKE_<LANG>_COMPILER_FRONTEND_VARIANT` in the toolchains object,
+    /// so it is inferred from the compiler id and the name of the compiler executable
+    /// (e.g. `clang-cl` is a Clang compiler with MSVC-style flags).
+    #[must_use]
+    pub fn frontend_variant(&self) -> Option<FrontendVariant> {
+        let stem = self
+            .path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .and_then(|stem| stem.to_str())
+            .map(str::to_ascii_lowercase);
+
+        if matches!(stem.as_deref(), Some("clang-cl" | "icx-cl" | "icl")) {
+            return Some(FrontendVariant::Msvc);
+        }
+
+        match self.parsed_id()? {
+            CompilerId::Msvc => Some(FrontendVariant::Msvc),
+            id if id.is_clang_based() => Some(FrontendVariant::Gnu),
+            CompilerId::Gnu | CompilerId::Intel | CompilerId::Lcc | CompilerId::Nvhpc => {
+                Some(FrontendVariant::Gnu)
+            }
+            _ => None,
+        }
+    }
+
+    /// MSVC toolset version (e.g. 14.38) derived from the compiler version (e.g. 19.38)
+    ///
+    /// Only available for the MSVC compiler.
+    #[must_use]
+    pub fn msvc_toolset(&self) -> Option<Version> {
+        if self.parsed_id()? != CompilerId::Msvc {
+            return None;
+        }
+
+        let version = self.parsed_version()?;
+        if version.major != 19 {
+            return None;
+        }
+
+        Some(Version::new(14, version.minor, 0))
+    }
 }
 
 impl Object for Toolchains {
@@ -167,7 +754,14 @@ mod tests {
 
         let toolchains = serde_json::from_value::<Toolchains>(json).unwrap();
         assert_eq!(toolchains.kind, ObjectKind::Toolchains);
-        assert_eq!(toolchains.version, MajorMinor { major: 1, minor: 0 });
+        assert_eq!(
+            toolchains.version,
+            MajorMinor {
+                major: 1,
+                minor: 0,
+                ..Default::default()
+            }
+        );
         assert_eq!(toolchains.toolchains.len(), 2);
         assert_eq!(toolchains.toolchains[0].language, "C");
         assert_eq!(toolchains.toolchains[1].language, "CXX");
@@ -181,4 +775,153 @@ mod tests {
             "GNU"
         );
     }
+
+    #[test]
+    fn test_source_file_extensions() {
+        use crate::objects::codemodel_v2::{CompileGroup, Configuration, Source, Target};
+
+        let toolchains = Toolchains {
+            toolchains: vec![
+                Toolchain {
+                    language: "C".into(),
+                    source_file_extensions: vec!["c".into(), "m".into()],
+                    ..Default::default()
+                },
+                Toolchain {
+                    language: "CXX".into(),
+                    source_file_extensions: vec!["C".into(), "cpp".into(), "cxx".into()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let language = |path: &str| {
+            toolchains
+                .toolchain_for_path(path)
+                .map(|toolchain| toolchain.language.as_str())
+        };
+        assert_eq!(language("src/main.c"), Some("C"));
+        assert_eq!(language("src/main.C"), Some("CXX"));
+        assert_eq!(language("src/main.CPP"), Some("CXX"));
+        assert_eq!(language("src/kernel.cu"), None);
+        assert_eq!(language("README"), None);
+
+        let codemodel = CodeModel {
+            configurations: vec![Configuration {
+                name: "Debug".into(),
+                targets: vec![Target {
+                    name: "app".into(),
+                    sources: vec![
+                        Source {
+                            path: "main.cpp".into(),
+                            compile_group_index: Some(0.into()),
+                            ..Default::default()
+                        },
+                        Source {
+                            path: "kernel.cu".into(),
+                            compile_group_index: Some(0.into()),
+                            ..Default::default()
+                        },
+                        Source {
+                            path: "legacy.c".into(),
+                            compile_group_index: Some(0.into()),
+                            ..Default::default()
+                        },
+                        Source {
+                            path: "main.h".into(),
+                            ..Default::default()
+                        },
+                    ],
+                    compile_groups: vec![CompileGroup {
+                        language: "CXX".into(),
+                        source_indexes: vec![0.into(), 1.into(), 2.into()],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let mismatches = toolchains.check_source_languages(&codemodel);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].source, PathBuf::from("kernel.cu"));
+        assert_eq!(mismatches[0].extension_language, None);
+        assert_eq!(mismatches[1].source, PathBuf::from("legacy.c"));
+        assert_eq!(mismatches[1].extension_language.as_deref(), Some("C"));
+        assert_eq!(mismatches[1].compile_group_language, "CXX");
+    }
+
+    #[test]
+    fn test_compiler_identification() {
+        let json = json!({
+          "path": "C:/Program Files/LLVM/bin/clang-cl.exe",
+          "id": "Clang",
+          "version": "17.0.6",
+          "target": "x86_64-pc-windows-msvc",
+          "implicit": {}
+        });
+
+        let compiler = serde_json::from_value::<Compiler>(json).unwrap();
+        assert_eq!(compiler.parsed_id(), Some(CompilerId::Clang));
+        assert!(compiler.parsed_version().unwrap() >= Version::new(17, 0, 0));
+        assert!(compiler.parsed_version().unwrap() < Version::new(17, 0, 7));
+        assert_eq!(compiler.frontend_variant(), Some(FrontendVariant::Msvc));
+        assert_eq!(compiler.msvc_toolset(), None);
+        assert_eq!(
+            compiler.parsed_target(),
+            Some(TargetTriple {
+                arch: "x86_64".into(),
+                vendor: Some("pc".into()),
+                os: Some("windows".into()),
+                env: Some("msvc".into()),
+            })
+        );
+
+        let msvc = Compiler {
+            id: Some("MSVC".into()),
+            version: Some("19.38.33133.0".into()),
+            ..Default::default()
+        };
+        assert_eq!(msvc.frontend_variant(), Some(FrontendVariant::Msvc));
+        assert_eq!(msvc.msvc_toolset(), Some(Version::new(14, 38, 0)));
+
+        assert_eq!(
+            TargetTriple::parse("arm-linux-gnueabihf"),
+            Some(TargetTriple {
+                arch: "arm".into(),
+                vendor: None,
+                os: Some("linux".into()),
+                env: Some("gnueabihf".into()),
+            })
+        );
+        assert_eq!(
+            CompilerId::from("SomethingNew"),
+            CompilerId::Other("SomethingNew".into())
+        );
+        for id in [
+            "Borland",
+            "Embarcadero",
+            "VisualAge",
+            "XL",
+            "Watcom",
+            "OpenWatcom",
+        ] {
+            let value = serde_json::to_value(CompilerId::from(id)).unwrap();
+            assert_eq!(value, json!(id));
+        }
+        assert_eq!(Version::parse("11"), Some(Version::new(11, 0, 0)));
+        assert_eq!(
+            Version::parse("19.38.33133.2"),
+            Some(Version {
+                major: 19,
+                minor: 38,
+                patch: 33133,
+                tweak: 2
+            })
+        );
+        assert_eq!(Version::parse("unknown"), None);
+    }
 }
@@ -0,0 +1,285 @@
+//! Path helpers for the forward-slash paths used throughout the cmake-file-api
+
+use std::path::Path;
+
+/// Lexically normalize a path
+///
+/// Backslashes are converted to forward slashes, `.` components and duplicate separators are removed
+/// and `..` components are resolved where possible. The file system is not accessed.
+pub(crate) fn normalize(path: &Path) -> String {
+    let raw = path.to_string_lossy().replace('\\', "/");
+
+    // keep leading root: `/`, `//server` (UNC) or a drive letter like `C:/`
+    let (root, rest) = if let Some(unc) = raw.strip_prefix("//") {
+        ("//", unc)
+    } else if let Some(absolute) = raw.strip_prefix('/') {
+        ("/", absolute)
+    } else {
+        ("", raw.as_str())
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." && !is_drive(last) => {
+                    components.pop();
+                }
+                _ if !root.is_empty() || components.first().map_or(false, |c| is_drive(c)) => {}
+                _ => components.push(".."),
+            },
+            _ => components.push(component),
+        }
+    }
+
+    let joined = components.join("/");
+    if joined.is_empty() && root.is_empty() {
+        ".".to_owned()
+    } else if components.len() == 1 && is_drive(components[0]) {
+        format!("{joined}/")
+    } else {
+        format!("{root}{joined}")
+    }
+}
+
+/// Resolve `path` against `base` if it is relative and normalize it
+pub(crate) fn resolve(base: &Path, path: &Path) -> String {
+    if is_absolute(path) {
+        normalize(path)
+    } else {
+        normalize(&base.join(path))
+    }
+}
+
+/// True for absolute paths in either Unix or Windows notation, independent of the host platform
+pub(crate) fn is_absolute(path: &Path) -> bool {
+    let raw = path.to_string_lossy();
+    let bytes = raw.as_bytes();
+    matches!(bytes.first(), Some(b'/' | b'\\'))
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'/' | b'\\'))
+}
+
+/// True if paths of the host file system are case-sensitive, false on Windows and macOS
+pub(crate) fn host_case_sensitive() -> bool {
+    !(cfg!(windows) || cfg!(target_os = "macos"))
+}
+
+/// Comparison key of a normalized path, lowercase if paths are case-insensitive
+pub(crate) fn case_key(normalized: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        normalized.to_owned()
+    } else {
+        normalized.to_lowercase()
+    }
+}
+
+/// True if `path` equals `dir` or is located below it; both must be normalized
+pub(crate) fn starts_with(path: &str, dir: &str) -> bool {
+    if dir == "." {
+        return !path.starts_with('/') && !path.starts_with("..");
+    }
+    path.strip_prefix(dir).map_or(false, |rest| {
+        rest.is_empty() || rest.starts_with('/') || dir.ends_with('/')
+    })
+}
+
+/// Replace the directory `old` with `new` if `path` is `old` or located below it; all must be normalized
+///
+/// Components are joined with a single separator, e.g. `C:/x.cpp` relocated from `C:/` to `D:/proj`
+/// is `D:/proj/x.cpp`.
+pub(crate) fn relocate(path: &str, old: &str, new: &str) -> Option<String> {
+    if !is_absolute(Path::new(path)) || !starts_with(path, old) {
+        return None;
+    }
+    let rest = path[old.len()..].trim_start_matches('/');
+    Some(if rest.is_empty() {
+        new.to_owned()
+    } else {
+        format!("{}/{rest}", new.trim_end_matches('/'))
+    })
+}
+
+/// Replace the directory `old` with `new` wherever a path in a command line fragment starts with it
+///
+/// Paths may follow a flag like in `-I/src/include` or `/IC:\src`, separators of the fragment are kept.
+/// `old` and `new` must be normalized.
+pub(crate) fn relocate_fragment(fragment: &str, old: &str, new: &str) -> Option<String> {
+    if !is_absolute(Path::new(old)) {
+        return None;
+    }
+    let bytes = fragment.as_bytes();
+    // `C:/` and `/` are matched without their trailing separator, the path continues with one
+    let root = old.trim_end_matches('/').as_bytes();
+    let drive_root = is_drive(old.get(..2).unwrap_or_default());
+
+    let mut relocated = String::new();
+    let mut changed = false;
+    let mut copied = 0;
+    let mut token = 0;
+    while token < bytes.len() {
+        if is_delimiter(bytes[token]) {
+            token += 1;
+            continue;
+        }
+
+        // the path starts the token or follows a flag: `-` or, for drive paths, `/` and flag characters
+        let flag = bytes[token] == b'-' || (drive_root && bytes[token] == b'/');
+        let mut start = token;
+        let matched = loop {
+            if let Some(end) = match_root(bytes, start, root) {
+                break Some(end);
+            }
+            start += 1;
+            let in_flag = flag
+                && start < bytes.len()
+                && (start == token + 1 || is_flag_char(bytes[start - 1]));
+            if !in_flag {
+                break None;
+            }
+        };
+
+        if let Some(end) = matched {
+            let windows = bytes[start..bytes.len().min(end + 1)].contains(&b'\\');
+            let replacement = if end == bytes.len() {
+                new
+            } else {
+                new.trim_end_matches('/')
+            };
+            relocated.push_str(&fragment[copied..start]);
+            if windows {
+                relocated.push_str(&replacement.replace('/', "\\"));
+            } else {
+                relocated.push_str(replacement);
+            }
+            changed = true;
+            copied = end;
+        }
+        // continue after the path, it may contain delimiters like spaces if it was relocated
+        let path_end = token.max(copied);
+        token = bytes[path_end..]
+            .iter()
+            .position(|byte| is_delimiter(*byte))
+            .map_or(bytes.len(), |length| path_end + length);
+    }
+
+    if !changed {
+        return None;
+    }
+    relocated.push_str(&fragment[copied..]);
+    Some(relocated)
+}
+
+/// End of `root` if it is found at `start` and ends at a component boundary
+fn match_root(bytes: &[u8], start: usize, root: &[u8]) -> Option<usize> {
+    let end = start + root.len();
+    let candidate = bytes.get(start..end)?;
+    let same = candidate
+        .iter()
+        .zip(root)
+        .all(|(a, b)| a == b || (is_separator(*a) && is_separator(*b)));
+    let boundary = match bytes.get(end) {
+        Some(byte) => is_separator(*byte) || (!root.is_empty() && is_delimiter(*byte)),
+        None => !root.is_empty(),
+    };
+    (same && boundary).then_some(end)
+}
+
+fn is_separator(byte: u8) -> bool {
+    matches!(byte, b'/' | b'\\')
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || matches!(byte, b'"' | b'\'' | b'=' | b',' | b';')
+}
+
+fn is_flag_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_')
+}
+
+fn is_drive(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(Path::new("/a/./b//c/../d")), "/a/b/d");
+        assert_eq!(
+            normalize(Path::new("C:\\src\\..\\lib\\x.cpp")),
+            "C:/lib/x.cpp"
+        );
+        assert_eq!(normalize(Path::new("C:/")), "C:/");
+        assert_eq!(normalize(Path::new("../a/../../b")), "../../b");
+        assert_eq!(normalize(Path::new("/..")), "/");
+        assert_eq!(normalize(Path::new("./")), ".");
+        assert_eq!(
+            resolve(Path::new("/src"), Path::new("lib/x.cpp")),
+            "/src/lib/x.cpp"
+        );
+        assert_eq!(
+            resolve(Path::new("/src"), Path::new("D:/x.cpp")),
+            "D:/x.cpp"
+        );
+        assert!(starts_with("/src/lib/x.cpp", "/src/lib"));
+        assert!(starts_with("/src/lib", "/src/lib"));
+        assert!(!starts_with("/src/library/x.cpp", "/src/lib"));
+        assert!(starts_with("C:/x.cpp", "C:/"));
+    }
+
+    #[test]
+    fn test_relocate() {
+        assert_eq!(
+            relocate("C:/x.cpp", "C:/", "D:/proj").as_deref(),
+            Some("D:/proj/x.cpp")
+        );
+        assert_eq!(relocate("/src", "/src", "/").as_deref(), Some("/"));
+        assert_eq!(relocate("/src2/x.cpp", "/src", "/home"), None);
+        assert_eq!(relocate("src/x.cpp", "src", "/home"), None);
+
+        assert_eq!(
+            relocate_fragment("-I/ci/src/include -I/ci/src2", "/ci/src", "/home/me").as_deref(),
+            Some("-I/home/me/include -I/ci/src2")
+        );
+        assert_eq!(
+            relocate_fragment("-Wl,-rpath,/ci/src/lib /ci/src", "/ci/src", "/home/me").as_deref(),
+            Some("-Wl,-rpath,/home/me/lib /home/me")
+        );
+        assert_eq!(
+            relocate_fragment("/IC:\\ci\\src /FoC:/ci/src/x.obj", "C:/ci/src", "D:/proj")
+                .as_deref(),
+            Some("/ID:\\proj /FoD:/proj/x.obj")
+        );
+        assert_eq!(
+            relocate_fragment("C:/x.cpp", "C:/", "D:/proj").as_deref(),
+            Some("D:/proj/x.cpp")
+        );
+        assert_eq!(
+            relocate_fragment("-isystem /usr/include", "/", "/sysroot").as_deref(),
+            Some("-isystem /sysroot/usr/include")
+        );
+        assert_eq!(
+            relocate_fragment("/opt/ci/src -DX=ci/src", "/ci/src", "/home"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert!(!is_absolute(Path::new("中文/main.cpp")));
+        assert!(!is_absolute(Path::new("é")));
+        assert!(is_absolute(Path::new("/中文/main.cpp")));
+        assert!(is_absolute(Path::new("C:\\中文")));
+        assert_eq!(
+            resolve(Path::new("/src"), Path::new("中文/./main.cpp")),
+            "/src/中文/main.cpp"
+        );
+    }
+}
@@ -1,9 +1,14 @@
-use crate::{index, objects, reply};
+use crate::{extras, index, objects};
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 
+pub mod fixture;
+pub mod snapshot;
+
 /// Errors for reading replies
 #[non_exhaustive]
 #[derive(Debug, thiserror::Error)]
@@ -52,6 +57,9 @@ pub struct Reader {
 
     /// Index file
     index: index::Index,
+
+    /// Reply files by `jsonFile` when reading from a snapshot instead of the build directory
+    snapshot_files: Option<BTreeMap<String, serde_json::Value>>,
 }
 
 impl Reader {
@@ -68,9 +76,22 @@ impl Reader {
         Ok(Reader {
             build_dir: build_dir.as_ref().to_path_buf(),
             index,
+            snapshot_files: None,
         })
     }
 
+    /// Create a new reader from a snapshot, see [`snapshot::Snapshot`]
+    ///
+    /// The build directory of the reader is the (possibly relocated) build directory of the snapshot.
+    #[must_use]
+    pub fn from_snapshot(snapshot: snapshot::Snapshot) -> Self {
+        Reader {
+            build_dir: snapshot.build_dir,
+            index: snapshot.index,
+            snapshot_files: Some(snapshot.files),
+        }
+    }
+
     #[must_use]
     pub fn build_dir(&self) -> &Path {
         &self.build_dir
@@ -97,14 +118,102 @@ impl Reader {
         let reply_reference = self
             .find_object(T::kind(), T::major())
             .ok_or(ReaderError::ObjectNotFound)?;
-        let reply_file = reply::dir(&self.build_dir).join(&reply_reference.json_file);
-        let mut object: T = Reader::parse_reply(reply_file)?;
+        let mut object: T = self.read_reply_file(&reply_reference.json_file)?;
 
         object.resolve_references(self)?;
 
         Ok(object)
     }
 
+    /// Parse a file of the reply directory, e.g. a target file referenced by the codemodel
+    ///
+    /// # Errors
+    ///
+    /// `ReaderError::ObjectNotFound`: if the file is not part of the snapshot
+    /// `ReaderError::IO`: if an IO error occurs while reading the file
+    /// `ReaderError::Parse`: if an error occurs while parsing the file
+    pub fn read_reply_file<P: AsRef<Path>, Object: DeserializeOwned>(
+        &self,
+        json_file: P,
+    ) -> Result<Object, ReaderError> {
+        match &self.snapshot_files {
+            Some(files) => {
+                let key = json_file.as_ref().to_string_lossy().replace('\\', "/");
+                let value = files.get(&key).ok_or(ReaderError::ObjectNotFound)?;
+                Ok(Object::deserialize(value)?)
+            }
+            None => Reader::parse_reply(dir(&self.build_dir).join(json_file)),
+        }
+    }
+
+    /// Report JSON fields of the reply which are not modeled by this crate (strict mode)
+    ///
+    /// Checks the index file, every object listed in the index and the target and directory files
+    /// of the codemodel. The index file is only checked when reading from a build directory.
+    /// See [`extras::unknown_fields`].
+    ///
+    /// # Errors
+    ///
+    /// `ReaderError::ObjectNotFound`: if a referenced file is not part of the snapshot
+    /// `ReaderError::IO`: if an IO error occurs while reading a file
+    /// `ReaderError::Parse`: if an error occurs while parsing a file
+    pub fn unknown_fields(&self) -> Result<Vec<extras::UnknownField>, ReaderError> {
+        let mut unknown = Vec::new();
+
+        if self.snapshot_files.is_none() {
+            if let Some(file) = index_file(&self.build_dir) {
+                let json: serde_json::Value = Reader::parse_reply(&file)?;
+                let file_name = file.file_name().map(PathBuf::from).unwrap_or_default();
+                push_unknown::<index::Index>(&file_name, &json, &mut unknown)?;
+            }
+        }
+
+        for object in &self.index.objects {
+            let json: serde_json::Value = self.read_reply_file(&object.json_file)?;
+            let file = &object.json_file;
+            match (object.kind, object.version.major) {
+                (objects::ObjectKind::CodeModel, 2) => {
+                    push_unknown::<objects::CodeModelV2>(file, &json, &mut unknown)?;
+
+                    let codemodel = objects::CodeModelV2::deserialize(&json)?;
+                    for config in &codemodel.configurations {
+                        for target in &config.target_refs {
+                            let target_json = self.read_reply_file(&target.json_file)?;
+                            push_unknown::<objects::codemodel_v2::Target>(
+                                &target.json_file,
+                                &target_json,
+                                &mut unknown,
+                            )?;
+                        }
+                        for directory in &config.directory_refs {
+                            let directory_json = self.read_reply_file(&directory.json_file)?;
+                            push_unknown::<objects::codemodel_v2::Directory>(
+                                &directory.json_file,
+                                &directory_json,
+                                &mut unknown,
+                            )?;
+                        }
+                    }
+                }
+                (objects::ObjectKind::Cache, 2) => {
+                    push_unknown::<objects::CacheV2>(file, &json, &mut unknown)?;
+                }
+                (objects::ObjectKind::CMakeFiles, 1) => {
+                    push_unknown::<objects::CMakeFilesV1>(file, &json, &mut unknown)?;
+                }
+                (objects::ObjectKind::Toolchains, 1) => {
+                    push_unknown::<objects::ToolchainsV1>(file, &json, &mut unknown)?;
+                }
+                (objects::ObjectKind::ConfigureLog, 1) => {
+                    push_unknown::<objects::ConfigureLogV1>(file, &json, &mut unknown)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(unknown)
+    }
+
     /// Parse a reply file into a given object type
     pub(crate) fn parse_reply<P: AsRef<Path>, Object: DeserializeOwned>(
         reply_file: P,
@@ -129,6 +238,22 @@ impl Reader {
     }
 }
 
+fn push_unknown<T: Serialize + DeserializeOwned>(
+    file: &Path,
+    json: &serde_json::Value,
+    unknown: &mut Vec<extras::UnknownField>,
+) -> Result<(), ReaderError> {
+    unknown.extend(
+        extras::unknown_fields::<T>(json)?
+            .into_iter()
+            .map(|pointer| extras::UnknownField {
+                file: file.to_path_buf(),
+                pointer,
+            }),
+    );
+    Ok(())
+}
+
 /// Get cmake-file-api reply path for a given build directory
 pub fn dir<P: AsRef<Path>>(build_dir: P) -> PathBuf {
     Path::new(build_dir.as_ref())
@@ -0,0 +1,776 @@
+//! Write synthetic cmake-file-api reply trees, e.g. for testing tools without running `CMake`
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cmake_file_api::{objects, reply};
+//! # let build_dir = std::path::Path::new(".");
+//!
+//! reply::fixture::Builder::default()
+//!   .codemodel(objects::CodeModelV2::default())
+//!   .cache(objects::CacheV2::default())
+//!   .client("my-tool", serde_json::json!({ "foo": "bar" }))
+//!   .write(build_dir)
+//!   .expect("Failed to write reply");
+//!
+//! let reader = reply::Reader::from_build_dir(build_dir).expect("Failed to read reply");
+//! ```
+//!
+//! A loaded reply can be exported into another build directory, e.g. to archive build metadata:
+//!
+//! ```no_run
+//! use cmake_file_api::reply;
+//! # let build_dir = std::path::Path::new(".");
+//! # let archive_dir = std::path::Path::new("archive");
+//!
+//! let reader = reply::Reader::from_build_dir(build_dir).expect("Failed to read reply");
+//! reply::fixture::Builder::from_reader(&reader)
+//!   .expect("Failed to read objects")
+//!   .write(archive_dir)
+//!   .expect("Failed to write reply");
+//! ```
+
+use crate::index::{
+    CMake, CMakeGenerator, CMakeVersion, ClientField, Index, QueryJson, ReplyField,
+    ReplyFileReference,
+};
+use crate::objects::codemodel_v2::{
+    Configuration, DirectoryIdx, DirectoryReference, Project, ProjectIdx, TargetReference,
+};
+use crate::objects::{
+    CMakeFilesV1, CacheV2, CodeModelV2, ConfigureLogV1, MajorMinor, Object, ToolchainsV1,
+};
+use crate::reply;
+use crate::reply::{Reader, ReaderError};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use std::{fs, io};
+
+/// Errors for writing reply fixtures
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum FixtureError {
+    #[error("IO error: {0}")]
+    IO(io::Error),
+
+    #[error("Failed to serialize reply: {0}")]
+    Parse(serde_json::Error),
+
+    #[error("No directory object for the source directory of target {0}")]
+    MissingDirectory(String),
+}
+
+impl From<io::Error> for FixtureError {
+    fn from(err: io::Error) -> Self {
+        FixtureError::IO(err)
+    }
+}
+
+impl From<serde_json::Error> for FixtureError {
+    fn from(err: serde_json::Error) -> Self {
+        FixtureError::Parse(err)
+    }
+}
+
+/// Builder for a complete `.cmake/api/v1/reply` tree
+///
+/// Every object is written to a content-hashed file, together with an `index-*.json` that references
+/// the objects as stateless replies and as client replies for each stateful query.
+/// The kind and, if unset, the major version of the objects are filled in.
+///
+/// Codemodel targets and directories are written to their own files. Missing target and directory
+/// references are synthesized and linked into the directory and project hierarchy, with a root project
+/// named after the top-level source directory if there are no projects. `jsonFile` members are set to
+/// the written files.
+#[derive(Default)]
+pub struct Builder {
+    cmake: Option<CMake>,
+    codemodel: Option<CodeModelV2>,
+    cache: Option<CacheV2>,
+    toolchains: Option<ToolchainsV1>,
+    cmake_files: Option<CMakeFilesV1>,
+    configure_log: Option<ConfigureLogV1>,
+    clients: Vec<(String, serde_json::Value)>,
+    replies: HashMap<String, ReplyField>,
+}
+
+impl Builder {
+    /// Builder with the index information, all objects and the reply section of a reply
+    ///
+    /// Resolved codemodel targets and directories are written to new files, so the exported
+    /// reply does not depend on the files of the original reply. The reply section, including
+    /// client queries, their responses and error entries, is kept as loaded.
+    ///
+    /// # Errors
+    ///
+    /// `ReaderError::IO`: if an IO error occurs while reading an object file
+    /// `ReaderError::Parse`: if an error occurs while parsing an object file
+    pub fn from_reader(reader: &Reader) -> Result<Self, ReaderError> {
+        let mut builder = Builder::default();
+        builder.cmake(reader.index().cmake.clone());
+        if reader.has_object::<CodeModelV2>() {
+            builder.codemodel(reader.read_object()?);
+        }
+        if reader.has_object::<ConfigureLogV1>() {
+            builder.configure_log(reader.read_object()?);
+        }
+        if reader.has_object::<CacheV2>() {
+            builder.cache(reader.read_object()?);
+        }
+        if reader.has_object::<CMakeFilesV1>() {
+            builder.cmake_files(reader.read_object()?);
+        }
+        if reader.has_object::<ToolchainsV1>() {
+            builder.toolchains(reader.read_object()?);
+        }
+
+        for (name, field) in &reader.index().reply {
+            builder.reply(name, field.clone());
+        }
+
+        Ok(builder)
+    }
+
+    /// Set `CMake` version, paths and generator of the index, defaults to `CMake` 3.27 with Ninja
+    pub fn cmake(&mut self, cmake: CMake) -> &mut Self {
+        self.cmake = Some(cmake);
+        self
+    }
+
+    pub fn codemodel(&mut self, mut codemodel: CodeModelV2) -> &mut Self {
+        codemodel.kind = CodeModelV2::kind();
+        set_major::<CodeModelV2>(&mut codemodel.version);
+        self.codemodel = Some(codemodel);
+        self
+    }
+
+    pub fn cache(&mut self, mut cache: CacheV2) -> &mut Self {
+        cache.kind = CacheV2::kind();
+        set_major::<CacheV2>(&mut cache.version);
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn toolchains(&mut self, mut toolchains: ToolchainsV1) -> &mut Self {
+        toolchains.kind = ToolchainsV1::kind();
+        set_major::<ToolchainsV1>(&mut toolchains.version);
+        self.toolchains = Some(toolchains);
+        self
+    }
+
+    pub fn cmake_files(&mut self, mut cmake_files: CMakeFilesV1) -> &mut Self {
+        cmake_files.kind = CMakeFilesV1::kind();
+        set_major::<CMakeFilesV1>(&mut cmake_files.version);
+        self.cmake_files = Some(cmake_files);
+        self
+    }
+
+    pub fn configure_log(&mut self, mut configure_log: ConfigureLogV1) -> &mut Self {
+        configure_log.kind = ConfigureLogV1::kind();
+        set_major::<ConfigureLogV1>(&mut configure_log.version);
+        self.configure_log = Some(configure_log);
+        self
+    }
+
+    /// Add a client with a stateful query that requests all objects of the fixture
+    ///
+    /// # Arguments
+    ///
+    /// * `client_name` - Client name
+    /// * `client_data` - Client data (JSON)
+    pub fn client(&mut self, client_name: &str, client_data: serde_json::Value) -> &mut Self {
+        self.clients.push((client_name.to_owned(), client_data));
+        self
+    }
+
+    /// Add an entry to the reply section of the index, e.g. an error for a requested object
+    ///
+    /// References to objects of the fixture are updated to the written files, other entries are
+    /// written as given. Entries replace the ones generated for the objects and the clients.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Reply name like `codemodel-v2` or `client-<client>`
+    /// * `field` - Reply entry
+    pub fn reply(&mut self, name: &str, field: ReplyField) -> &mut Self {
+        self.replies.insert(name.to_owned(), field);
+        self
+    }
+
+    /// Write the reply tree into the build directory
+    ///
+    /// Existing index files are removed so that the written index is the one found by [`reply::Reader`].
+    ///
+    /// # Errors
+    ///
+    /// `FixtureError::IO`: if the reply directory or a file can not be written
+    /// `FixtureError::Parse`: if an object can not be serialized
+    pub fn write<P: AsRef<Path>>(&self, build_dir: P) -> Result<Index, FixtureError> {
+        let reply_dir = reply::dir(build_dir);
+        fs::create_dir_all(&reply_dir)?;
+        for entry in fs::read_dir(&reply_dir)? {
+            let path = entry?.path();
+            let is_index = path
+                .file_name()
+                .and_then(OsStr::to_str)
+                .map_or(false, |name| name.starts_with("index-"));
+            if is_index && path.is_file() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        let mut objects = Vec::new();
+        if let Some(codemodel) = &self.codemodel {
+            objects.push(write_codemodel(&reply_dir, codemodel.clone())?);
+        }
+        if let Some(configure_log) = &self.configure_log {
+            objects.push(write_object(&reply_dir, configure_log)?);
+        }
+        if let Some(cache) = &self.cache {
+            objects.push(write_object(&reply_dir, cache)?);
+        }
+        if let Some(cmake_files) = &self.cmake_files {
+            objects.push(write_object(&reply_dir, cmake_files)?);
+        }
+        if let Some(toolchains) = &self.toolchains {
+            objects.push(write_object(&reply_dir, toolchains)?);
+        }
+
+        let mut replies: HashMap<String, ReplyField> = objects
+            .iter()
+            .map(|object| {
+                (
+                    format!("{}-v{}", object.kind.as_str(), object.version.major),
+                    ReplyField::ReplyFileReference(object.clone()),
+                )
+            })
+            .collect();
+        for (client_name, client_data) in &self.clients {
+            let requests: Vec<serde_json::Value> = objects
+                .iter()
+                .map(|object| {
+                    serde_json::json!({
+                        "kind": object.kind.as_str(),
+                        "version": object.version.major
+                    })
+                })
+                .collect();
+            let query = QueryJson {
+                client: Some(client_data.clone()),
+                requests: Some(serde_json::Value::Array(requests)),
+                responses: Some(serde_json::to_value(&objects)?),
+                ..Default::default()
+            };
+            replies.insert(
+                format!("client-{client_name}"),
+                ReplyField::Client(HashMap::from([(
+                    "query.json".to_owned(),
+                    ClientField::QueryJson(query),
+                )])),
+            );
+        }
+        for (name, field) in &self.replies {
+            replies.insert(name.clone(), relink_reply(field.clone(), &objects)?);
+        }
+
+        let index = Index {
+            cmake: self.cmake.clone().unwrap_or_else(default_cmake),
+            objects,
+            reply: replies,
+            ..Default::default()
+        };
+        let content = serde_json::to_string_pretty(&index)?;
+        fs::write(
+            reply_dir.join(format!("index-{}.json", content_hash(&content))),
+            content,
+        )?;
+
+        Ok(index)
+    }
+}
+
+/// Point the object references of a reply entry to the written objects of the same kind and major version
+fn relink_reply(
+    field: ReplyField,
+    objects: &[ReplyFileReference],
+) -> Result<ReplyField, FixtureError> {
+    Ok(match field {
+        ReplyField::ReplyFileReference(reference) => {
+            ReplyField::ReplyFileReference(relink(reference, objects))
+        }
+        ReplyField::Client(client) => ReplyField::Client(
+            client
+                .into_iter()
+                .map(|(name, client_field)| {
+                    let relinked = match client_field {
+                        ClientField::ReplyFileReference(reference) => {
+                            ClientField::ReplyFileReference(relink(reference, objects))
+                        }
+                        ClientField::QueryJson(mut query) => {
+                            if let Some(serde_json::Value::Array(responses)) = &mut query.responses
+                            {
+                                for response in responses {
+                                    // errors for objects that could not be generated stay as they are
+                                    if response.get("jsonFile").is_some() {
+                                        let reference = serde_json::from_value(response.clone())?;
+                                        *response =
+                                            serde_json::to_value(relink(reference, objects))?;
+                                    }
+                                }
+                            }
+                            ClientField::QueryJson(query)
+                        }
+                        other => other,
+                    };
+                    Ok((name, relinked))
+                })
+                .collect::<Result<_, FixtureError>>()?,
+        ),
+        other => other,
+    })
+}
+
+fn relink(reference: ReplyFileReference, objects: &[ReplyFileReference]) -> ReplyFileReference {
+    objects
+        .iter()
+        .find(|object| {
+            object.kind == reference.kind && object.version.major == reference.version.major
+        })
+        .cloned()
+        .unwrap_or(reference)
+}
+
+fn set_major<T: Object>(version: &mut MajorMinor) {
+    if version.major == 0 {
+        version.major = T::major();
+    }
+}
+
+fn default_cmake() -> CMake {
+    CMake {
+        version: CMakeVersion {
+            major: 3,
+            minor: 27,
+            patch: 0,
+            suffix: String::new(),
+            string: "3.27.0".to_owned(),
+            is_dirty: false,
+            ..Default::default()
+        },
+        generator: CMakeGenerator {
+            multi_config: false,
+            name: "Ninja".to_owned(),
+            platform: None,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Write an object to `<kind>-v<major>-<hash>.json`
+fn write_object<T: Object + Serialize>(
+    reply_dir: &Path,
+    object: &T,
+) -> Result<ReplyFileReference, FixtureError> {
+    let content = serde_json::to_string_pretty(object)?;
+    // the version is read back to reference the written minor version
+    let version: MajorMinor =
+        serde_json::from_value(serde_json::to_value(object)?["version"].clone())?;
+    let prefix = format!("{}-v{}", T::kind().as_str(), T::major());
+    Ok(ReplyFileReference {
+        kind: T::kind(),
+        version,
+        json_file: write_file(reply_dir, &prefix, &content)?,
+        ..Default::default()
+    })
+}
+
+fn write_codemodel(
+    reply_dir: &Path,
+    mut codemodel: CodeModelV2,
+) -> Result<ReplyFileReference, FixtureError> {
+    let project_name = codemodel.paths.source.file_name().map_or_else(
+        || "Project".to_owned(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    for config in &mut codemodel.configurations {
+        add_references(config, &project_name)?;
+
+        for (index, directory) in config.directories.iter().enumerate() {
+            let source = directory.paths.source.display().to_string();
+            let prefix = format!("directory-{}-{}", file_name_part(&source), config.name);
+            config.directory_refs[index].json_file = write_file(
+                reply_dir,
+                &prefix,
+                &serde_json::to_string_pretty(directory)?,
+            )?;
+        }
+
+        for (index, target) in config.targets.iter().enumerate() {
+            let prefix = format!("target-{}-{}", file_name_part(&target.name), config.name);
+            config.target_refs[index].json_file =
+                write_file(reply_dir, &prefix, &serde_json::to_string_pretty(target)?)?;
+        }
+    }
+
+    write_object(reply_dir, &codemodel)
+}
+
+/// Synthesize the missing directory and target references, including their back references
+fn add_references(config: &mut Configuration, project_name: &str) -> Result<(), FixtureError> {
+    let first_directory = config.directory_refs.len();
+    let first_target = config.target_refs.len();
+    if first_directory >= config.directories.len() && first_target >= config.targets.len() {
+        return Ok(());
+    }
+    if config.projects.is_empty() {
+        config.projects.push(Project {
+            name: project_name.to_owned(),
+            ..Default::default()
+        });
+    }
+
+    config.directory_refs.extend(
+        config.directories[first_directory..]
+            .iter()
+            .map(|directory| DirectoryReference {
+                source: directory.paths.source.clone(),
+                build: directory.paths.build.clone(),
+                has_install_rule: !directory.installers.is_empty(),
+                ..Default::default()
+            }),
+    );
+
+    // parents before their subdirectories, so the project of the parent is known
+    let mut added: Vec<usize> = (first_directory..config.directory_refs.len()).collect();
+    added.sort_by_key(|&index| depth(&config.directory_refs[index].source));
+    for index in added {
+        let source = &config.directory_refs[index].source;
+        let parent = config
+            .directory_refs
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| is_subdirectory(source, &candidate.source))
+            .max_by_key(|(_, candidate)| depth(&candidate.source))
+            .map(|(parent, _)| parent);
+
+        let project_index = match parent {
+            Some(parent_index) => {
+                config.directory_refs[parent_index]
+                    .child_indexes
+                    .push(index.into());
+                config.directory_refs[parent_index].project_index
+            }
+            None => ProjectIdx::default(),
+        };
+        let directory_ref = &mut config.directory_refs[index];
+        directory_ref.parent_index = parent.map(DirectoryIdx::from);
+        directory_ref.project_index = project_index;
+        if let Some(project) = config.projects.get_mut(project_index.get()) {
+            project.directory_indexes.push(index.into());
+        }
+    }
+
+    for index in first_target..config.targets.len() {
+        let target = &config.targets[index];
+        let directory = config
+            .directories
+            .iter()
+            .position(|directory| directory.paths.source == target.paths.source)
+            .ok_or_else(|| FixtureError::MissingDirectory(target.name.clone()))?;
+        let project_index = config.directory_refs[directory].project_index;
+        config.target_refs.push(TargetReference {
+            name: target.name.clone(),
+            id: target.id.clone(),
+            directory_index: directory.into(),
+            project_index,
+            ..Default::default()
+        });
+        config.directory_refs[directory]
+            .target_indexes
+            .push(index.into());
+        if let Some(project) = config.projects.get_mut(project_index.get()) {
+            project.target_indexes.push(index.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of components of a directory relative to the top-level directory, `.` has none
+fn depth(path: &Path) -> usize {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .count()
+}
+
+/// True if `path` is a subdirectory of `parent`, both relative to the top-level directory
+fn is_subdirectory(path: &Path, parent: &Path) -> bool {
+    depth(parent) < depth(path) && (depth(parent) == 0 || path.starts_with(parent))
+}
+
+fn write_file(reply_dir: &Path, prefix: &str, content: &str) -> Result<PathBuf, FixtureError> {
+    let file_name = PathBuf::from(format!("{prefix}-{}.json", content_hash(content)));
+    fs::write(reply_dir.join(&file_name), content)?;
+    Ok(file_name)
+}
+
+/// Replace characters that are not allowed in file names, like `CMake` does for directory objects
+fn file_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '<' | '>' | '"' | '|' | '?' | '*' => '_',
+            _ => c,
+        })
+        .collect()
+}
+
+/// Stable content hash (64-bit FNV-1a), the file api does not prescribe a hash function
+fn content_hash(content: &str) -> String {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Target object from the members in `json`, for tests
+///
+/// Missing required members are filled in: `id` is `<name>::@1`, `type` is `EXECUTABLE`, `paths` point to
+/// the top-level directory and `backtraceGraph` is empty.
+#[cfg(test)]
+pub(crate) fn target(mut json: serde_json::Value) -> crate::objects::codemodel_v2::Target {
+    let defaults = serde_json::json!({
+        "id": format!("{}::@1", json["name"].as_str().unwrap_or_default()),
+        "type": "EXECUTABLE",
+        "paths": { "source": ".", "build": "." },
+        "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+    });
+    if let (Some(members), serde_json::Value::Object(default_members)) =
+        (json.as_object_mut(), defaults)
+    {
+        for (key, value) in default_members {
+            members.entry(key).or_insert(value);
+        }
+    }
+    serde_json::from_value(json).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::{Configuration, Directory, Target};
+    use crate::objects::{CacheV2, CodeModelV2, ToolchainsV1};
+    use crate::reply::fixture::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fixture() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "paths": { "source": ".", "build": "." },
+            "sources": [ { "path": "main.cpp" } ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+        let directory: Directory = serde_json::from_value(json!({
+            "paths": { "source": ".", "build": "." },
+            "installers": [],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.source = "/src".into();
+        codemodel.paths.build = "/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Debug".into(),
+            targets: vec![target],
+            directories: vec![directory],
+            ..Default::default()
+        });
+        let cache: CacheV2 = serde_json::from_value(json!({
+            "kind": "cache",
+            "version": { "major": 2, "minor": 0 },
+            "entries": [ { "name": "CMAKE_BUILD_TYPE", "value": "Debug", "type": "STRING", "properties": [] } ]
+        }))
+        .unwrap();
+
+        let tmp_dir = tempdir::TempDir::new("test_fixture").unwrap();
+        let index = Builder::default()
+            .codemodel(codemodel.clone())
+            .cache(cache.clone())
+            .toolchains(ToolchainsV1::default())
+            .client("test", json!({ "answer": 42 }))
+            .write(tmp_dir.path())
+            .unwrap();
+        assert_eq!(index.objects.len(), 3);
+        assert!(index.reply.contains_key("client-test"));
+        assert!(index.reply.contains_key("codemodel-v2"));
+
+        let reader = Reader::from_build_dir(tmp_dir.path()).unwrap();
+        assert_eq!(reader.index(), &index);
+        assert_eq!(reader.read_object::<CacheV2>().unwrap(), cache);
+
+        let read_codemodel = reader.read_object::<CodeModelV2>().unwrap();
+        let config = &read_codemodel.configurations[0];
+        assert_eq!(config.targets, codemodel.configurations[0].targets);
+        assert_eq!(config.directories, codemodel.configurations[0].directories);
+        assert_eq!(config.target_refs[0].name, "app");
+        assert!(config.target_refs[0]
+            .json_file
+            .to_string_lossy()
+            .starts_with("target-app-Debug-"));
+
+        // export the loaded reply into another build directory
+        let export_dir = tempdir::TempDir::new("test_fixture_export").unwrap();
+        let exported_index = Builder::from_reader(&reader)
+            .unwrap()
+            .write(export_dir.path())
+            .unwrap();
+        assert_eq!(exported_index, index);
+
+        let exported = Reader::from_build_dir(export_dir.path()).unwrap();
+        assert_eq!(
+            exported.read_object::<CodeModelV2>().unwrap(),
+            read_codemodel
+        );
+    }
+
+    #[test]
+    fn test_fixture_hierarchy() {
+        let directories = [".", "lib", "lib/inner"].map(|source| {
+            serde_json::from_value::<Directory>(json!({
+                "paths": { "source": source, "build": source },
+                "installers": [],
+                "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+            }))
+            .unwrap()
+        });
+
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.source = "/src/demo".into();
+        codemodel.configurations.push(Configuration {
+            // subdirectories listed before their parent
+            directories: vec![
+                directories[2].clone(),
+                directories[1].clone(),
+                directories[0].clone(),
+            ],
+            targets: vec![
+                target(json!({ "name": "app" })),
+                target(json!({
+                    "name": "inner",
+                    "paths": { "source": "lib/inner", "build": "lib/inner" }
+                })),
+            ],
+            ..Default::default()
+        });
+
+        let tmp_dir = tempdir::TempDir::new("test_fixture_hierarchy").unwrap();
+        Builder::default()
+            .codemodel(codemodel.clone())
+            .write(tmp_dir.path())
+            .unwrap();
+
+        let reader = Reader::from_build_dir(tmp_dir.path()).unwrap();
+        let config = &reader.read_object::<CodeModelV2>().unwrap().configurations[0];
+        assert!(config.validate().is_empty());
+        assert_eq!(config.root_project().unwrap().name, "demo");
+        assert_eq!(
+            config
+                .project_targets("demo")
+                .unwrap()
+                .map(|target| target.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["app", "inner"]
+        );
+        assert_eq!(
+            config.defining_directory("inner").unwrap().source,
+            PathBuf::from("lib/inner")
+        );
+        assert_eq!(
+            config
+                .directory_ancestors(DirectoryIdx::new(0))
+                .unwrap()
+                .map(|directory| directory.source.display().to_string())
+                .collect::<Vec<_>>(),
+            vec!["lib", "."]
+        );
+
+        // targets need a directory object
+        codemodel.configurations[0].directories.clear();
+        assert!(matches!(
+            Builder::default().codemodel(codemodel).write(tmp_dir.path()),
+            Err(FixtureError::MissingDirectory(name)) if name == "app"
+        ));
+    }
+
+    #[test]
+    fn test_fixture_reply() {
+        let cache: CacheV2 = serde_json::from_value(json!({
+            "kind": "cache",
+            "version": { "major": 2, "minor": 0 },
+            "entries": []
+        }))
+        .unwrap();
+        let client_reply: ReplyField = serde_json::from_value(json!({
+            "query.json": {
+                "client": { "stateful": true },
+                "requests": [
+                    { "kind": "cache", "version": 2 },
+                    { "kind": "toolchains", "version": 9 }
+                ],
+                "responses": [
+                    { "kind": "cache", "version": { "major": 2, "minor": 0 }, "jsonFile": "cache-v2.json" },
+                    { "error": "unknown request kind version 'toolchains-v9'" }
+                ]
+            },
+            "cache-v2": { "kind": "cache", "version": { "major": 2, "minor": 0 }, "jsonFile": "cache-v2.json" }
+        }))
+        .unwrap();
+        let error_reply: ReplyField =
+            serde_json::from_value(json!({ "error": "unknown request kind 'foo'" })).unwrap();
+
+        let tmp_dir = tempdir::TempDir::new("test_fixture_reply").unwrap();
+        let index = Builder::default()
+            .cache(cache)
+            .client("test", json!({ "answer": 42 }))
+            .reply("client-stateful", client_reply)
+            .reply("foo-v1", error_reply.clone())
+            .write(tmp_dir.path())
+            .unwrap();
+
+        // references to the objects point to the written files
+        let cache_file = &index.objects[0].json_file;
+        let cache_reference = json!({
+            "kind": "cache",
+            "version": { "major": 2, "minor": 0 },
+            "jsonFile": cache_file
+        });
+        let written = serde_json::to_value(&index.reply["client-stateful"]).unwrap();
+        assert_eq!(written["cache-v2"], cache_reference);
+        assert_eq!(
+            written["query.json"]["responses"],
+            json!([
+                cache_reference,
+                { "error": "unknown request kind version 'toolchains-v9'" }
+            ])
+        );
+        assert_eq!(index.reply["foo-v1"], error_reply);
+
+        // the reply section survives an export unchanged
+        let reader = Reader::from_build_dir(tmp_dir.path()).unwrap();
+        let export_dir = tempdir::TempDir::new("test_fixture_reply_export").unwrap();
+        let exported_index = Builder::from_reader(&reader)
+            .unwrap()
+            .write(export_dir.path())
+            .unwrap();
+        assert_eq!(exported_index, index);
+        assert!(reply::dir(export_dir.path()).join(cache_file).is_file());
+    }
+}
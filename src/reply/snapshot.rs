@@ -0,0 +1,412 @@
+//! Single-file snapshots of a complete cmake-file-api reply
+//!
+//! A snapshot bundles the index, every object and the codemodel target and directory files,
+//! e.g. to ship the reply of a CI build as one artifact. Source and build directories can be
+//! relocated, so the model stays useful on another machine.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use cmake_file_api::{objects, reply};
+//! # let build_dir = std::path::Path::new(".");
+//!
+//! let reader = reply::Reader::from_build_dir(build_dir).expect("Failed to read reply");
+//! let snapshot = reply::snapshot::Snapshot::from_reader(&reader).expect("Failed to read reply files");
+//! snapshot.write_json("reply.json").expect("Failed to write snapshot");
+//!
+//! // on another machine
+//! let mut snapshot = reply::snapshot::Snapshot::read_json("reply.json").expect("Failed to read snapshot");
+//! snapshot.relocate_source_dir("/home/me/project");
+//! snapshot.relocate_build_dir("/home/me/project/build");
+//! let reader = reply::Reader::from_snapshot(snapshot);
+//! let codemodel: objects::CodeModelV2 = reader.read_object().expect("Failed to read codemodel");
+//! ```
+
+use crate::index::Index;
+use crate::paths;
+use crate::reply::{Reader, ReaderError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors for reading and writing snapshots
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    #[error("IO error: {0}")]
+    IO(io::Error),
+
+    #[error("Failed to (de)serialize snapshot: {0}")]
+    Parse(serde_json::Error),
+
+    #[cfg(feature = "snapshot-cbor")]
+    #[error("Failed to (de)serialize CBOR snapshot: {0}")]
+    Cbor(String),
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::IO(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::Parse(err)
+    }
+}
+
+/// A complete reply in a single value
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Snapshot {
+    /// Version of the snapshot format.
+    pub format_version: u32,
+
+    /// Top-level source directory, taken from the codemodel if available.
+    pub source_dir: Option<PathBuf>,
+
+    /// Top-level build directory.
+    pub build_dir: PathBuf,
+
+    /// Index of the reply.
+    pub index: Index,
+
+    /// Content of the reply files by their `jsonFile` path relative to the reply directory.
+    pub files: BTreeMap<String, Value>,
+}
+
+impl Snapshot {
+    /// Current version of the snapshot format
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /// Collect all objects of a reply and the files referenced by them
+    ///
+    /// # Errors
+    ///
+    /// `ReaderError::IO`: if an IO error occurs while reading a reply file
+    /// `ReaderError::Parse`: if an error occurs while parsing a reply file
+    pub fn from_reader(reader: &Reader) -> Result<Self, ReaderError> {
+        let mut snapshot = Snapshot {
+            format_version: Snapshot::FORMAT_VERSION,
+            source_dir: None,
+            build_dir: reader.build_dir().to_path_buf(),
+            index: reader.index().clone(),
+            files: BTreeMap::new(),
+        };
+
+        let mut pending: Vec<String> = reader
+            .index()
+            .objects
+            .iter()
+            .map(|object| json_file_key(&object.json_file))
+            .collect();
+        while let Some(json_file) = pending.pop() {
+            if snapshot.files.contains_key(&json_file) {
+                continue;
+            }
+            let content: Value = reader.read_reply_file(&json_file)?;
+            collect_json_files(&content, &mut pending);
+            snapshot.files.insert(json_file, content);
+        }
+
+        // prefer the absolute paths of the codemodel over the build directory given to the reader
+        if let Some(codemodel) = snapshot
+            .index
+            .objects
+            .iter()
+            .find(|object| object.kind == crate::objects::ObjectKind::CodeModel)
+        {
+            let paths_value = &snapshot.files[&json_file_key(&codemodel.json_file)]["paths"];
+            if let Some(source) = paths_value["source"].as_str() {
+                snapshot.source_dir = Some(PathBuf::from(source));
+            }
+            if let Some(build) = paths_value["build"].as_str() {
+                snapshot.build_dir = PathBuf::from(build);
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Move the top-level source directory, all paths inside of it are rewritten
+    ///
+    /// Paths inside of command line fragments, e.g. `-I<source dir>/include`, are rewritten as well.
+    ///
+    /// Does nothing if the source directory is unknown.
+    pub fn relocate_source_dir<P: AsRef<Path>>(&mut self, source_dir: P) {
+        if let Some(old) = self.source_dir.as_ref().map(|dir| paths::normalize(dir)) {
+            self.relocate(&old, &paths::normalize(source_dir.as_ref()));
+        }
+    }
+
+    /// Move the top-level build directory, all paths inside of it are rewritten
+    pub fn relocate_build_dir<P: AsRef<Path>>(&mut self, build_dir: P) {
+        let old = paths::normalize(&self.build_dir);
+        self.relocate(&old, &paths::normalize(build_dir.as_ref()));
+    }
+
+    /// Write the snapshot as JSON
+    ///
+    /// # Errors
+    ///
+    /// `SnapshotError::IO`: if the file can not be written
+    /// `SnapshotError::Parse`: if the snapshot can not be serialized
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Snapshot::write_json`]
+    ///
+    /// # Errors
+    ///
+    /// `SnapshotError::IO`: if the file can not be read
+    /// `SnapshotError::Parse`: if the file is not a valid snapshot
+    pub fn read_json<P: AsRef<Path>>(path: P) -> Result<Self, SnapshotError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Write the snapshot in the compact binary CBOR format
+    ///
+    /// # Errors
+    ///
+    /// `SnapshotError::IO`: if the file can not be written
+    /// `SnapshotError::Cbor`: if the snapshot can not be serialized
+    #[cfg(feature = "snapshot-cbor")]
+    pub fn write_cbor<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let mut content = Vec::new();
+        ciborium::ser::into_writer(self, &mut content)
+            .map_err(|err| SnapshotError::Cbor(err.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Snapshot::write_cbor`]
+    ///
+    /// # Errors
+    ///
+    /// `SnapshotError::IO`: if the file can not be read
+    /// `SnapshotError::Cbor`: if the file is not a valid snapshot
+    #[cfg(feature = "snapshot-cbor")]
+    pub fn read_cbor<P: AsRef<Path>>(path: P) -> Result<Self, SnapshotError> {
+        let content = fs::read(path)?;
+        ciborium::de::from_reader(content.as_slice())
+            .map_err(|err| SnapshotError::Cbor(err.to_string()))
+    }
+
+    fn relocate(&mut self, old: &str, new: &str) {
+        for content in self.files.values_mut() {
+            rewrite_root(content, old, new);
+        }
+        if let Some(source_dir) = &mut self.source_dir {
+            rewrite_path(source_dir, old, new);
+        }
+        rewrite_path(&mut self.build_dir, old, new);
+    }
+}
+
+fn json_file_key(json_file: &Path) -> String {
+    json_file.to_string_lossy().replace('\\', "/")
+}
+
+/// Collect `jsonFile` members, e.g. the target and directory references of the codemodel
+fn collect_json_files(value: &Value, json_files: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, member) in map {
+                match (key.as_str(), member) {
+                    ("jsonFile", Value::String(json_file)) => json_files.push(json_file.clone()),
+                    _ => collect_json_files(member, json_files),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for member in values {
+                collect_json_files(member, json_files);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rewrite_path(path: &mut PathBuf, old: &str, new: &str) {
+    if let Some(rewritten) = paths::relocate(&paths::normalize(path), old, new) {
+        *path = PathBuf::from(rewritten);
+    }
+}
+
+/// Rewrite paths below `old` in all strings, including paths inside of command line fragments
+fn rewrite_root(value: &mut Value, old: &str, new: &str) {
+    match value {
+        Value::String(text) => {
+            if let Some(rewritten) = paths::relocate_fragment(text, old, new) {
+                *text = rewritten;
+            }
+        }
+        Value::Array(values) => {
+            for member in values {
+                rewrite_root(member, old, new);
+            }
+        }
+        Value::Object(map) => {
+            for member in map.values_mut() {
+                rewrite_root(member, old, new);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::objects::codemodel_v2::{Configuration, Directory, Target};
+    use crate::objects::CodeModelV2;
+    use crate::reply::fixture::Builder;
+    use crate::reply::snapshot::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "paths": { "source": ".", "build": "." },
+            "sources": [ { "path": "/ci/src/main.cpp" }, { "path": "/ci/src-extra/util.cpp" } ],
+            "compileGroups": [{
+                "language": "CXX",
+                "sourceIndexes": [0, 1],
+                "compileCommandFragments": [ { "fragment": "-I/ci/src/include -isystem /ci/src-extra" } ]
+            }],
+            "artifacts": [ { "path": "/ci/src/build/app" } ],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+        let directory: Directory = serde_json::from_value(json!({
+            "paths": { "source": ".", "build": "." },
+            "installers": [],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.source = "/ci/src".into();
+        codemodel.paths.build = "/ci/src/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Release".into(),
+            targets: vec![target],
+            directories: vec![directory],
+            ..Default::default()
+        });
+
+        let build_dir = tempdir::TempDir::new("test_snapshot").unwrap();
+        Builder::default()
+            .codemodel(codemodel)
+            .write(build_dir.path())
+            .unwrap();
+        let reader = Reader::from_build_dir(build_dir.path()).unwrap();
+
+        let mut snapshot = Snapshot::from_reader(&reader).unwrap();
+        assert_eq!(snapshot.files.len(), 3);
+        assert_eq!(snapshot.build_dir, PathBuf::from("/ci/src/build"));
+
+        let snapshot_file = build_dir.path().join("snapshot.json");
+        snapshot.write_json(&snapshot_file).unwrap();
+        assert_eq!(Snapshot::read_json(&snapshot_file).unwrap(), snapshot);
+
+        #[cfg(feature = "snapshot-cbor")]
+        {
+            let cbor_file = build_dir.path().join("snapshot.cbor");
+            snapshot.write_cbor(&cbor_file).unwrap();
+            assert_eq!(Snapshot::read_cbor(&cbor_file).unwrap(), snapshot);
+        }
+
+        snapshot.relocate_source_dir("/home/me/project");
+        assert_eq!(snapshot.build_dir, PathBuf::from("/home/me/project/build"));
+
+        let relocated_codemodel: CodeModelV2 =
+            Reader::from_snapshot(snapshot).read_object().unwrap();
+        assert_eq!(
+            relocated_codemodel.paths.build,
+            PathBuf::from("/home/me/project/build")
+        );
+        let relocated_target = &relocated_codemodel.configurations[0].targets[0];
+        assert_eq!(
+            relocated_target.sources[0].path,
+            PathBuf::from("/home/me/project/main.cpp")
+        );
+        assert_eq!(
+            relocated_target.sources[1].path,
+            PathBuf::from("/ci/src-extra/util.cpp")
+        );
+        assert_eq!(
+            relocated_target.artifacts[0].path,
+            PathBuf::from("/home/me/project/build/app")
+        );
+        assert_eq!(
+            relocated_target.compile_groups[0].compile_command_fragments[0].fragment,
+            "-I/home/me/project/include -isystem /ci/src-extra"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_drive_root() {
+        let target: Target = serde_json::from_value(json!({
+            "name": "app",
+            "id": "app::@6890427a1f51a3e7e1df",
+            "type": "EXECUTABLE",
+            "paths": { "source": ".", "build": "build" },
+            "sources": [ { "path": "C:/x.cpp" } ],
+            "compileGroups": [{
+                "language": "CXX",
+                "sourceIndexes": [0],
+                "compileCommandFragments": [ { "fragment": "/IC:\\ci\\src /DAPP" } ]
+            }],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+        let directory: Directory = serde_json::from_value(json!({
+            "paths": { "source": ".", "build": "build" },
+            "installers": [],
+            "backtraceGraph": { "commands": [], "files": [], "nodes": [] }
+        }))
+        .unwrap();
+        let mut codemodel = CodeModelV2::default();
+        codemodel.paths.source = "C:/".into();
+        codemodel.paths.build = "C:/build".into();
+        codemodel.configurations.push(Configuration {
+            name: "Release".into(),
+            targets: vec![target],
+            directories: vec![directory],
+            ..Default::default()
+        });
+
+        let build_dir = tempdir::TempDir::new("test_snapshot_drive_root").unwrap();
+        Builder::default()
+            .codemodel(codemodel)
+            .write(build_dir.path())
+            .unwrap();
+        let reader = Reader::from_build_dir(build_dir.path()).unwrap();
+
+        let mut snapshot = Snapshot::from_reader(&reader).unwrap();
+        snapshot.relocate_source_dir("D:/proj");
+        assert_eq!(snapshot.source_dir, Some(PathBuf::from("D:/proj")));
+        assert_eq!(snapshot.build_dir, PathBuf::from("D:/proj/build"));
+
+        let relocated_codemodel: CodeModelV2 =
+            Reader::from_snapshot(snapshot).read_object().unwrap();
+        let relocated_target = &relocated_codemodel.configurations[0].targets[0];
+        assert_eq!(
+            relocated_target.sources[0].path,
+            PathBuf::from("D:/proj/x.cpp")
+        );
+        assert_eq!(
+            relocated_target.compile_groups[0].compile_command_fragments[0].fragment,
+            "/ID:\\proj\\ci\\src /DAPP"
+        );
+    }
+}
@@ -6,14 +6,14 @@ fn test_missing_api() {
     let empty_dir = tmp_dir.path();
 
     // Test that the API is not available when the directory is empty
-    assert_eq!(reply::is_available(&empty_dir), false);
+    assert!(!reply::is_available(empty_dir));
 
     // Test that the index_file function returns None when the directory is empty
-    assert!(reply::index_file(&empty_dir).is_none());
+    assert!(reply::index_file(empty_dir).is_none());
 
     // Test for cmake_file_api::CMakeFileApiError::FileApiNotGenerated
     assert!(matches!(
-        reply::Reader::from_build_dir(&empty_dir),
+        reply::Reader::from_build_dir(empty_dir),
         Err(reply::ReaderError::FileApiNotGenerated)
     ));
 }
@@ -24,29 +24,28 @@ fn test_json_parser_error() {
     let build_dir = tmp_dir.path();
 
     // create empty reply dir
-    std::fs::create_dir_all(&reply::dir(&build_dir)).unwrap();
+    std::fs::create_dir_all(reply::dir(build_dir)).unwrap();
 
     // create broken index file
-    let broken_index_file = reply::dir(&build_dir).join("index-broken.json");
+    let broken_index_file = reply::dir(build_dir).join("index-broken.json");
     std::fs::write(&broken_index_file, "broken").unwrap();
 
     // Test that the API is available when the reply directory exists
-    assert_eq!(reply::is_available(&build_dir), true);
+    assert!(reply::is_available(build_dir));
 
     // Test that the index_file function returns None when the index file is missing
     assert_eq!(
-        reply::index_file(&build_dir),
+        reply::index_file(build_dir),
         Some(broken_index_file.clone())
     );
 
     // Test ReaderError::Parse
     assert!(matches!(
-        reply::Reader::from_build_dir(&build_dir),
+        reply::Reader::from_build_dir(build_dir),
         Err(reply::ReaderError::Parse(_))
     ));
 }
 
-///
 #[test]
 fn test_valid_api() {
     let tmp_dir = tempdir::TempDir::new("test_cmake").unwrap();
@@ -122,7 +121,7 @@ fn test_valid_api() {
     // run cmake
     assert!(std::process::Command::new("cmake")
         .arg("-S")
-        .arg(&project_dir)
+        .arg(project_dir)
         .arg("-B")
         .arg(&build_dir)
         .status()
@@ -156,10 +155,10 @@ fn test_valid_api() {
 
     // Test that the CMakeFileApi object can be used to get the codemodel
     let codemodel: objects::CodeModelV2 = reader.read_object().expect("codemodel should be available");
-    assert!(codemodel.configurations.len() > 0);
+    assert!(!codemodel.configurations.is_empty());
 
     // targets should not be empty
-    assert!(codemodel.configurations[0].targets.len() > 0);
+    assert!(!codemodel.configurations[0].targets.is_empty());
 
     // targets and target_refs should have the same length
     assert_eq!(
@@ -168,7 +167,7 @@ fn test_valid_api() {
     );
 
     // directories should not be empty
-    assert!(codemodel.configurations[0].directories.len() > 0);
+    assert!(!codemodel.configurations[0].directories.is_empty());
 
     // directories and directory_refs should have the same length
     assert_eq!(